@@ -0,0 +1,141 @@
+use rand::seq::SliceRandom;
+
+use crate::{error::Result, game::{Move, Query}, observer::GameObserver, player::{GoFishPlayer, Observation, PlayerView}, probability::Probability, tournament::Entrant, util::{self, GameRng}};
+
+/// Plays a uniformly random legal move
+#[derive(Clone)]
+pub struct RandomBot {
+    rng: GameRng,
+}
+
+/// Asks for the rank it holds the most cards of, from the player holding the most cards
+#[derive(Clone)]
+pub struct MostHeldBot;
+
+/// Asks the player the [`GameObserver`] estimates to hold the most cards of a rank it holds
+///
+/// Ties are broken like [`MostHeldBot`] picks its moves: by the rank it holds the most cards of, then by the player
+/// holding the most cards, and then by the lowest seat and rank.
+#[derive(Clone)]
+pub struct LikelyHolderBot {
+    pub observer: GameObserver,
+}
+
+/// Remembers every card it has seen change hands and only trusts what it knows for certain
+///
+/// It asks for cards a player is known to hold and otherwise asks for its most held rank from a player who hasn't
+/// been seen to run out of it.
+#[derive(Clone)]
+pub struct CountingBot {
+    pub observer: GameObserver,
+}
+
+impl RandomBot {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: util::rng_from_seed(seed),
+        }
+    }
+}
+
+impl LikelyHolderBot {
+    pub fn new(observer: GameObserver) -> Self {
+        Self {
+            observer,
+        }
+    }
+}
+
+impl CountingBot {
+    pub fn new(observer: GameObserver) -> Self {
+        Self {
+            observer,
+        }
+    }
+}
+
+impl GoFishPlayer for RandomBot {
+    fn choose_move(&mut self, view: &PlayerView) -> Result<Option<Move>> {
+        Ok(view.legal_moves().choose(&mut self.rng).copied())
+    }
+}
+
+impl GoFishPlayer for MostHeldBot {
+    fn choose_move(&mut self, view: &PlayerView) -> Result<Option<Move>> {
+        Ok(best_move(view, |query| (view.hand[query.card], view.hand_sizes[query.player])))
+    }
+}
+
+impl GoFishPlayer for LikelyHolderBot {
+    fn choose_move(&mut self, view: &PlayerView) -> Result<Option<Move>> {
+        let observer = &self.observer;
+
+        Ok(best_move(view, |query| {
+            let estimate = observer.other_players[observer.other_id(query.player)].cards.cards[query.card].value();
+
+            (estimate, view.hand[query.card], view.hand_sizes[query.player])
+        }))
+    }
+
+    fn observe(&mut self, observation: &Observation) -> Result<()> {
+        self.observer.observe(observation)
+    }
+
+    fn observer(&self) -> Option<&GameObserver> {
+        Some(&self.observer)
+    }
+}
+
+impl GoFishPlayer for CountingBot {
+    fn choose_move(&mut self, view: &PlayerView) -> Result<Option<Move>> {
+        let observer = &self.observer;
+
+        Ok(best_move(view, |query| {
+            let (known, ruled_out) = match observer.other_players[observer.other_id(query.player)].cards.cards[query.card] {
+                Probability::Known(amount) => (amount, amount == 0),
+                Probability::MoreThan(amount) => (amount, false),
+                Probability::Unknown(_) => (0, false),
+            };
+
+            (known, !ruled_out, view.hand[query.card], view.hand_sizes[query.player])
+        }))
+    }
+
+    fn observe(&mut self, observation: &Observation) -> Result<()> {
+        self.observer.observe(observation)
+    }
+
+    fn observer(&self) -> Option<&GameObserver> {
+        Some(&self.observer)
+    }
+}
+
+/// Every baseline with its name, to be entered in a [`Tournament`](crate::tournament::Tournament)
+///
+/// Random bots are seeded with the deal and their seat, so they play differently in every deal but the same way
+/// whenever a deal is played again from the same seat.
+pub fn entrants() -> Vec<(String, Entrant<'static>)> {
+    vec![
+        ("random".to_string(), Box::new(|observer: GameObserver, seed| Box::new(RandomBot::new(util::derive_seed(seed, observer.id as u64))) as Box<dyn GoFishPlayer>)),
+        ("most-held".to_string(), Box::new(|_, _| Box::new(MostHeldBot))),
+        ("likely-holder".to_string(), Box::new(|observer, _| Box::new(LikelyHolderBot::new(observer)))),
+        ("counting".to_string(), Box::new(|observer, _| Box::new(CountingBot::new(observer)))),
+    ]
+}
+
+/// Picks up if the player has to, and otherwise picks the legal query rated highest, the first one on ties
+pub(crate) fn best_move<K: PartialOrd>(view: &PlayerView, rate: impl Fn(&Query) -> K) -> Option<Move> {
+    let legal_moves = view.legal_moves();
+
+    if legal_moves.contains(&Move::Pickup) {
+        return Some(Move::Pickup);
+    }
+
+    legal_moves.into_iter()
+        .filter_map(|m| match m {
+            Move::Query(query) => Some((rate(&query), m)),
+            Move::Pickup => None,
+        })
+        .reduce(|best, next| if next.0 > best.0 { next } else { best })
+        .map(|(_, m)| m)
+}
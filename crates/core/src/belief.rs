@@ -0,0 +1,682 @@
+use std::collections::{BTreeMap, HashMap};
+
+use rand::Rng;
+
+use crate::{error::{GoFishError, Result}, observer::{KnownDeck, World}, probability::Probability, util};
+
+/// What an observer believes about where the cards it can't see are
+///
+/// For each rank there is a joint distribution over how its unseen copies are split between the other players and
+/// the stock, which is updated on every observation. A player is only tracked for a rank once something has been
+/// observed about how many of it they hold. Until then their copies belong to the rest, which is spread over the
+/// stock and the hands of the untracked players as if it was dealt at random.
+///
+/// Observations about how many of a rank a single player holds, i.e. asks, failed asks and placed sets, are exact
+/// updates of that rank's distribution, starting from the player's share of the rest the first time. Everything else
+/// is only approximated, as every rank is updated on its own:
+///
+/// - The rest of a rank is spread over the room of each hand, which only leaves out the fewest cards the hand is
+///   known to hold of the other ranks, while the whole stock is room. How likely more cards of the other ranks are,
+///   or that some of them have to be in the stock, doesn't move the rest.
+/// - A drawn card, whether another player or the observer drew it, is of a rank as often as the top of the stock
+///   would be if the rests were spread like that, so the same goes for draws.
+/// - The hand sizes only rule out splits which can't fit into or fill a hand, without weighing the others by how
+///   well the other ranks fit around them.
+/// - A player is only known not to hold a complete set of the ranks they are tracked for.
+///
+/// Weighing every rank against the others would take a distribution over how every rank is split at once, which has
+/// far too many outcomes to keep for a whole deck. [`Beliefs::deals`] does weigh the ranks against each other, by the
+/// hand sizes and without complete sets, for the deals which are sampled or gone through.
+#[derive(Debug, Clone)]
+pub struct Beliefs {
+    pub ranks: Vec<RankBelief>,
+    /// The amount of cards in the hand of each other player, followed by the amount of cards in the stock
+    pub sizes: Vec<usize>,
+}
+
+/// The joint distribution over how the unseen copies of one rank are split
+#[derive(Debug, Clone)]
+pub struct RankBelief {
+    /// The other players whose amount of the rank is tracked, in the order of the splits
+    pub tracked: Vec<usize>,
+    /// The probability of each split, which holds the amount of each tracked player followed by the rest
+    pub splits: BTreeMap<Vec<usize>, f64>,
+}
+
+impl RankBelief {
+    /// The index of a player in the splits, if they are tracked
+    pub fn position(&self, player: usize) -> Option<usize> {
+        self.tracked.iter().position(|&tracked| tracked == player)
+    }
+
+    /// The probability of each amount of copies being part of the rest
+    fn rests(&self) -> BTreeMap<usize, f64> {
+        let mut rests = BTreeMap::new();
+
+        for (split, &probability) in &self.splits {
+            *rests.entry(split[split.len() - 1]).or_insert(0.) += probability;
+        }
+
+        rests
+    }
+
+    /// The least and most copies which are part of the rest
+    fn rest_range(&self) -> (usize, usize) {
+        range(self.splits.keys().map(|split| split[split.len() - 1]))
+    }
+
+    /// Keeps only the splits for which `keep` is true, without changing anything if there are none
+    fn retain(&mut self, keep: impl Fn(&[usize]) -> bool) -> bool {
+        let splits = self.splits.iter()
+            .filter(|(split, _)| keep(split))
+            .map(|(split, &probability)| (split.clone(), probability))
+            .collect::<BTreeMap<_, _>>();
+
+        if splits.is_empty() {
+            return false;
+        }
+
+        self.splits = splits;
+        self.normalize();
+
+        true
+    }
+
+    fn normalize(&mut self) {
+        let total = self.splits.values().sum::<f64>();
+
+        for probability in self.splits.values_mut() {
+            *probability /= total;
+        }
+    }
+}
+
+impl Beliefs {
+    /// Starts from a random deal, where `unseen` holds the amount of each rank the observer can't see and `sizes`
+    /// the amount of cards of each other player followed by the stock
+    pub fn new(unseen: &[usize], sizes: Vec<usize>) -> Self {
+        Self {
+            ranks: unseen.iter().map(|&unseen| RankBelief {
+                tracked: Vec::new(),
+                splits: BTreeMap::from([(vec![unseen], 1.)]),
+            }).collect(),
+            sizes,
+        }
+    }
+
+    /// The index of the stock in `sizes`
+    pub fn stock(&self) -> usize {
+        self.sizes.len() - 1
+    }
+
+    /// The probability of each amount of a rank being in a hand or the stock, where the stock is at [`Beliefs::stock`]
+    pub fn distribution(&self, location: usize, rank: usize) -> Vec<f64> {
+        self.distributions(rank, &self.rooms()).swap_remove(location)
+    }
+
+    /// The estimate of each rank in each hand followed by the stock, as used by a
+    /// [`GameObserver`](crate::observer::GameObserver)
+    ///
+    /// An amount is known if nothing else is possible and more than something if there is a lower bound. Otherwise
+    /// it is estimated by its expected value.
+    pub fn estimates(&self) -> Vec<Vec<Probability>> {
+        let rooms = self.rooms();
+        let mut estimates = vec![Vec::with_capacity(self.ranks.len()); self.sizes.len()];
+
+        for (rank, belief) in self.ranks.iter().enumerate() {
+            let rest_room = self.rest_room(rank, &rooms);
+            let rests = belief.rests();
+
+            for (location, estimates) in estimates.iter_mut().enumerate() {
+                // The least, most and expected amount, where the rest is spread evenly over its room on average
+                let (amounts, mean) = match belief.position(location) {
+                    Some(i) => (
+                        range(belief.splits.iter().filter(|(_, &p)| p > 0.).map(|(split, _)| split[i])),
+                        belief.splits.iter().map(|(split, p)| split[i] as f64 * p).sum::<f64>(),
+                    ),
+                    None => {
+                        let room = rooms[location][rank];
+                        let rests = rests.iter().filter(|(_, &p)| p > 0.);
+
+                        (
+                            (
+                                rests.clone().map(|(&rest, _)| rest.saturating_sub(rest_room - room)).min().unwrap_or(0),
+                                rests.clone().map(|(&rest, _)| rest.min(room)).max().unwrap_or(0),
+                            ),
+                            rests.map(|(&rest, p)| (rest * room) as f64 / rest_room.max(1) as f64 * p).sum::<f64>(),
+                        )
+                    },
+                };
+
+                estimates.push(match amounts {
+                    (min, max) if min == max => Probability::Known(min),
+                    (min, _) if min > 0 => Probability::MoreThan(min),
+                    _ => Probability::Unknown(mean as f32),
+                });
+            }
+        }
+
+        estimates
+    }
+
+    /// Whether a player is tracked for a rank
+    pub fn is_tracked(&self, rank: usize, player: usize) -> bool {
+        self.ranks[rank].position(player).is_some()
+    }
+
+    /// The deals of the unseen cards which agree with the beliefs, where nobody holds a complete set as it would
+    /// have been placed
+    ///
+    /// For every rank, a split is picked and each copy of its rest goes to any untracked hand or the stock at random.
+    /// Every deal is weighted by how likely this is for all ranks, among the deals which fill every hand and the stock
+    /// exactly.
+    pub fn deals(&self, book_size: usize) -> Deals {
+        let stock = self.stock();
+
+        let options = self.ranks.iter()
+            .map(|belief| {
+                let untracked = (0..self.sizes.len()).filter(|&location| belief.position(location).is_none()).collect::<Vec<_>>();
+                let mut options = Vec::new();
+
+                for (split, &probability) in &belief.splits {
+                    let mut held = vec![0; self.sizes.len()];
+
+                    for (&player, &amount) in belief.tracked.iter().zip(split) {
+                        held[player] = amount;
+                    }
+
+                    let fits = |held: &[usize]| held.iter().enumerate().all(|(location, &amount)| {
+                        amount <= self.sizes[location] && (location == stock || amount < book_size)
+                    });
+
+                    if probability <= 0. || !fits(&held) {
+                        continue;
+                    }
+
+                    let rest = split[split.len() - 1];
+                    let mut spread = |held: &[usize], ways: f64| {
+                        if fits(held) {
+                            options.push((held.to_vec(), probability * ways / (untracked.len() as f64).powi(rest as i32)));
+                        }
+                    };
+
+                    spread_rest(&mut held, &untracked, rest, 1., &mut spread);
+                }
+
+                options
+            })
+            .collect();
+
+        Deals {
+            options,
+            sizes: self.sizes.clone(),
+            completions: HashMap::new(),
+        }
+    }
+
+    /// Keeps only what agrees with a player holding an amount of a rank for which `keep` is true
+    pub(crate) fn condition(&mut self, rank: usize, player: usize, keep: impl Fn(usize) -> bool) -> Result<()> {
+        self.track(rank, player)?;
+
+        let belief = &mut self.ranks[rank];
+        let i = belief.position(player).expect("The player was just tracked");
+
+        if belief.retain(|split| keep(split[i])) {
+            Ok(())
+        } else {
+            Err(GoFishError::InconsistentObservation(format!("Other player {player} can't hold the amount of {rank} they were observed to")))
+        }
+    }
+
+    /// Changes the amount of a rank a player holds, e.g. when they receive or give away cards
+    pub(crate) fn change(&mut self, rank: usize, player: usize, change: impl Fn(usize) -> usize) -> Result<()> {
+        self.track(rank, player)?;
+
+        let belief = &mut self.ranks[rank];
+        let i = belief.position(player).expect("The player was just tracked");
+        let mut splits = BTreeMap::new();
+
+        for (split, &probability) in &belief.splits {
+            let mut split = split.clone();
+            split[i] = change(split[i]);
+            *splits.entry(split).or_insert(0.) += probability;
+        }
+
+        belief.splits = splits;
+
+        Ok(())
+    }
+
+    /// Observes another player picking up a card they don't show
+    ///
+    /// The card is as likely to be any card in the room of the rest of a rank, which only takes the other ranks into
+    /// account through the room they leave. Nothing changes for the ranks the player isn't tracked for, as the card
+    /// stays part of the rest.
+    pub(crate) fn draw(&mut self, player: usize) -> Result<()> {
+        let stock = self.stock();
+
+        if self.sizes[stock] == 0 {
+            return Err(GoFishError::InconsistentObservation(format!("Other player {player} picked up from an empty stock")));
+        }
+
+        let rooms = self.rooms();
+
+        for rank in 0..self.ranks.len() {
+            let Some(i) = self.ranks[rank].position(player) else {
+                continue;
+            };
+
+            let room = self.rest_room(rank, &rooms) as f64;
+            let belief = &mut self.ranks[rank];
+            let mut splits = BTreeMap::new();
+
+            for (split, &probability) in &belief.splits {
+                let rest = split.len() - 1;
+                // The top card of the stock is as likely to be any card of the rest
+                let drawn = split[rest] as f64 / room;
+
+                if drawn < 1. {
+                    *splits.entry(split.clone()).or_insert(0.) += probability * (1. - drawn);
+                }
+
+                if split[rest] > 0 {
+                    let mut split = split.clone();
+                    split[i] += 1;
+                    split[rest] -= 1;
+                    *splits.entry(split).or_insert(0.) += probability * drawn;
+                }
+            }
+
+            belief.splits = splits;
+            belief.normalize();
+        }
+
+        self.sizes[player] += 1;
+        self.sizes[stock] -= 1;
+
+        Ok(())
+    }
+
+    /// Observes the observing player picking up a card of `rank`
+    pub(crate) fn reveal_draw(&mut self, rank: usize) -> Result<()> {
+        let stock = self.stock();
+        let room = self.rest_room(rank, &self.rooms()) as f64;
+        let belief = &mut self.ranks[rank];
+        let mut splits = BTreeMap::new();
+
+        for (split, &probability) in &belief.splits {
+            let rest = split.len() - 1;
+
+            if split[rest] > 0 {
+                let mut split = split.clone();
+                split[rest] -= 1;
+                *splits.entry(split).or_insert(0.) += probability * (split[rest] + 1) as f64 / room;
+            }
+        }
+
+        if splits.is_empty() || self.sizes[stock] == 0 {
+            return Err(GoFishError::InconsistentObservation(format!("The stock can't have held {rank}")));
+        }
+
+        belief.splits = splits;
+        belief.normalize();
+        self.sizes[stock] -= 1;
+
+        Ok(())
+    }
+
+    /// Rules out everything which doesn't fit into the hands, tracking players where needed
+    pub(crate) fn settle(&mut self) -> Result<()> {
+        loop {
+            let rooms = self.rooms();
+            let mut changed = false;
+
+            for rank in 0..self.ranks.len() {
+                let room = self.rest_room(rank, &rooms);
+
+                if self.ranks[rank].rest_range().1 > room {
+                    if !self.ranks[rank].retain(|split| split[split.len() - 1] <= room) {
+                        return Err(GoFishError::InconsistentObservation(format!("There isn't enough room for every copy of {rank}")));
+                    }
+
+                    changed = true;
+                }
+            }
+
+            for player in 0..self.stock() {
+                let ranges = (0..self.ranks.len()).map(|rank| self.held_range(player, rank, &rooms)).collect::<Vec<_>>();
+                let min = ranges.iter().map(|range| range.0).sum::<usize>();
+                let max = ranges.iter().map(|range| range.1).sum::<usize>();
+                let size = self.sizes[player];
+
+                if min > size || max < size {
+                    return Err(GoFishError::InconsistentObservation(format!("Other player {player} can't hold {size} cards")));
+                }
+
+                for (rank, &(low, high)) in ranges.iter().enumerate() {
+                    // What the other ranks leave of the hand
+                    let fits = size.saturating_sub(max - high)..=size - (min - low);
+
+                    if !fits.contains(&low) || !fits.contains(&high) {
+                        self.condition(rank, player, |held| fits.contains(&held))?;
+                        changed = true;
+                    }
+                }
+            }
+
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Starts tracking a player for a rank by dealing them their share of the rest
+    fn track(&mut self, rank: usize, player: usize) -> Result<()> {
+        if self.is_tracked(rank, player) {
+            return Ok(());
+        }
+
+        let rooms = self.rooms();
+        let room = rooms[player][rank];
+        let rest_room = self.rest_room(rank, &rooms);
+        let belief = &mut self.ranks[rank];
+        let mut splits = BTreeMap::new();
+
+        for (split, &probability) in &belief.splits {
+            let rest = split[split.len() - 1];
+
+            for held in rest.saturating_sub(rest_room - room)..=rest.min(room) {
+                let mut split = split.clone();
+                split.insert(split.len() - 1, held);
+                *split.last_mut().unwrap() -= held;
+
+                let share = util::binomial(room, held) * util::binomial(rest_room - room, rest - held) / util::binomial(rest_room, rest);
+                *splits.entry(split).or_insert(0.) += probability * share;
+            }
+        }
+
+        if splits.is_empty() {
+            return Err(GoFishError::InconsistentObservation(format!("There isn't enough room for every copy of {rank}")));
+        }
+
+        belief.tracked.push(player);
+        belief.splits = splits;
+        belief.normalize();
+
+        Ok(())
+    }
+
+    /// The room each hand and the stock has for the copies of each rank which are part of the rest, by location and
+    /// rank
+    ///
+    /// A hand has room for what isn't taken up by the least it holds of the other ranks it is tracked for.
+    fn rooms(&self) -> Vec<Vec<usize>> {
+        let mut known = vec![vec![0; self.ranks.len()]; self.stock()];
+
+        for (rank, belief) in self.ranks.iter().enumerate() {
+            for (i, &player) in belief.tracked.iter().enumerate() {
+                known[player][rank] = belief.splits.keys().map(|split| split[i]).min().unwrap_or(0);
+            }
+        }
+
+        let mut rooms = known.into_iter()
+            .zip(&self.sizes)
+            .map(|(known, size)| {
+                let taken = known.iter().sum::<usize>();
+
+                known.iter().map(|held| size.saturating_sub(taken - held)).collect()
+            })
+            .collect::<Vec<_>>();
+
+        rooms.push(vec![self.sizes[self.stock()]; self.ranks.len()]);
+
+        rooms
+    }
+
+    /// The room the rest of a rank is spread over
+    fn rest_room(&self, rank: usize, rooms: &[Vec<usize>]) -> usize {
+        (0..self.sizes.len())
+            .filter(|&location| !self.is_tracked(rank, location))
+            .map(|location| rooms[location][rank])
+            .sum()
+    }
+
+    /// The least and most of a rank a player can hold
+    fn held_range(&self, player: usize, rank: usize, rooms: &[Vec<usize>]) -> (usize, usize) {
+        let belief = &self.ranks[rank];
+
+        match belief.position(player) {
+            Some(i) => range(belief.splits.keys().map(|split| split[i])),
+            None => {
+                let (min, max) = belief.rest_range();
+                let room = rooms[player][rank];
+
+                (min.saturating_sub(self.rest_room(rank, rooms) - room), max.min(room))
+            },
+        }
+    }
+
+    /// The probability of each amount of a rank being in each hand and the stock
+    fn distributions(&self, rank: usize, rooms: &[Vec<usize>]) -> Vec<Vec<f64>> {
+        let belief = &self.ranks[rank];
+        let rest_room = self.rest_room(rank, rooms);
+        let rests = belief.rests();
+        let mut distributions = vec![Vec::new(); self.sizes.len()];
+
+        let add = |distribution: &mut Vec<f64>, amount: usize, probability: f64| {
+            if distribution.len() <= amount {
+                distribution.resize(amount + 1, 0.);
+            }
+
+            distribution[amount] += probability;
+        };
+
+        for (location, distribution) in distributions.iter_mut().enumerate() {
+            if let Some(i) = belief.position(location) {
+                for (split, &probability) in &belief.splits {
+                    add(distribution, split[i], probability);
+                }
+            } else {
+                let room = rooms[location][rank];
+
+                for (&rest, &probability) in &rests {
+                    for held in rest.saturating_sub(rest_room - room)..=rest.min(room) {
+                        add(distribution, held, probability * util::binomial(room, held) * util::binomial(rest_room - room, rest - held) / util::binomial(rest_room, rest));
+                    }
+                }
+            }
+        }
+
+        distributions
+    }
+}
+
+/// The deals of [`Beliefs::deals`], which can be sampled from or enumerated
+///
+/// The total weight of the deals of the later ranks is remembered for every room left, so it pays off to sample many
+/// deals from the same `Deals`.
+#[derive(Debug, Clone)]
+pub struct Deals {
+    /// The amount in each location of every way to spread each rank with its weight, by rank
+    options: Vec<Vec<(Vec<usize>, f64)>>,
+    sizes: Vec<usize>,
+    /// The total weight of dealing the ranks from a rank on into the room left in each location
+    completions: HashMap<(usize, Vec<usize>), f64>,
+}
+
+impl Deals {
+    /// Picks a deal at random according to its weight
+    pub fn sample(&mut self, rng: &mut impl Rng) -> Result<World> {
+        let mut room = self.sizes.clone();
+
+        if self.completions(0, &room) <= 0. {
+            return Err(GoFishError::InconsistentObservation("No deal agrees with the beliefs".to_string()));
+        }
+
+        let mut dealt = Vec::with_capacity(self.options.len());
+
+        for rank in 0..self.options.len() {
+            let weights = (0..self.options[rank].len())
+                .map(|i| {
+                    let (amounts, weight) = self.options[rank][i].clone();
+
+                    match subtract(&room, &amounts) {
+                        Some(left) => weight * self.completions(rank + 1, &left),
+                        None => 0.,
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let picked = choose(weights.into_iter(), rng).expect("A deal of the later ranks fits the room left");
+            let amounts = self.options[rank][picked].0.clone();
+
+            for (room, amount) in room.iter_mut().zip(&amounts) {
+                *room -= amount;
+            }
+
+            dealt.push(amounts);
+        }
+
+        let mut held = (0..self.sizes.len())
+            .map(|location| dealt.iter().map(|amounts| amounts[location]).collect())
+            .collect::<Vec<KnownDeck>>();
+
+        let stock = held.pop().expect("The stock always has a place");
+
+        Ok(World { hands: held, stock })
+    }
+
+    /// Every deal with its probability, or `None` if there are more than `max_worlds`
+    pub fn worlds(&mut self, max_worlds: usize) -> Result<Option<Vec<(World, f64)>>> {
+        let total = self.completions(0, &self.sizes.clone());
+
+        if total <= 0. {
+            return Err(GoFishError::InconsistentObservation("No deal agrees with the beliefs".to_string()));
+        }
+
+        let mut worlds = Vec::new();
+        let mut held = vec![vec![0; self.options.len()]; self.sizes.len()];
+
+        if !self.enumerate(0, &self.sizes.clone(), 1. / total, &mut held, &mut worlds, max_worlds) {
+            return Ok(None);
+        }
+
+        Ok(Some(worlds))
+    }
+
+    /// Goes through the deals of the ranks from `rank` on, returning `false` once there are too many
+    fn enumerate(&mut self, rank: usize, room: &[usize], probability: f64, held: &mut [KnownDeck], worlds: &mut Vec<(World, f64)>, max_worlds: usize) -> bool {
+        if rank == self.options.len() {
+            let mut hands = held.to_vec();
+            let stock = hands.pop().expect("The stock always has a place");
+
+            worlds.push((World { hands, stock }, probability));
+
+            return worlds.len() <= max_worlds;
+        }
+
+        for i in 0..self.options[rank].len() {
+            let (amounts, weight) = self.options[rank][i].clone();
+
+            let Some(left) = subtract(room, &amounts) else {
+                continue;
+            };
+
+            if self.completions(rank + 1, &left) <= 0. {
+                continue;
+            }
+
+            for (location, &amount) in amounts.iter().enumerate() {
+                held[location][rank] = amount;
+            }
+
+            if !self.enumerate(rank + 1, &left, probability * weight, held, worlds, max_worlds) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// The total weight of dealing the ranks from `rank` on into `room`
+    fn completions(&mut self, rank: usize, room: &[usize]) -> f64 {
+        if rank == self.options.len() {
+            return if room.iter().all(|&room| room == 0) { 1. } else { 0. };
+        }
+
+        if let Some(&weight) = self.completions.get(&(rank, room.to_vec())) {
+            return weight;
+        }
+
+        let mut weight = 0.;
+
+        for i in 0..self.options[rank].len() {
+            let (amounts, option) = self.options[rank][i].clone();
+
+            if let Some(left) = subtract(room, &amounts) {
+                weight += option * self.completions(rank + 1, &left);
+            }
+        }
+
+        self.completions.insert((rank, room.to_vec()), weight);
+
+        weight
+    }
+}
+
+/// Calls `spread` with every way to spread `rest` copies over the `untracked` locations together with the number of
+/// ways to pick which copies go where
+fn spread_rest(held: &mut [usize], untracked: &[usize], rest: usize, ways: f64, spread: &mut impl FnMut(&[usize], f64)) {
+    let Some((&location, others)) = untracked.split_first() else {
+        if rest == 0 {
+            spread(held, ways);
+        }
+
+        return;
+    };
+
+    let amounts = if others.is_empty() { rest..=rest } else { 0..=rest };
+
+    for amount in amounts {
+        held[location] = amount;
+        spread_rest(held, others, rest - amount, ways * util::binomial(rest, amount), spread);
+    }
+
+    held[location] = 0;
+}
+
+/// What is left of `room` after taking `amounts`, if it fits
+fn subtract(room: &[usize], amounts: &[usize]) -> Option<Vec<usize>> {
+    room.iter().zip(amounts).map(|(room, amount)| room.checked_sub(*amount)).collect()
+}
+
+/// Picks an index at random with a probability proportional to its weight, or `None` if every weight is 0
+fn choose(weights: impl Iterator<Item = f64>, rng: &mut impl Rng) -> Option<usize> {
+    let weights = weights.collect::<Vec<_>>();
+    let total = weights.iter().sum::<f64>();
+
+    if total <= 0. {
+        return None;
+    }
+
+    let mut pick = rng.gen_range(0. ..total);
+
+    for (i, &weight) in weights.iter().enumerate() {
+        if pick < weight {
+            return Some(i);
+        }
+
+        pick -= weight;
+    }
+
+    // Rounding can leave a tiny bit of weight over
+    weights.iter().rposition(|&weight| weight > 0.)
+}
+
+/// The smallest and largest amount, or 0 for both without any
+fn range(amounts: impl Iterator<Item = usize>) -> (usize, usize) {
+    amounts.fold(None, |range: Option<(usize, usize)>, amount| match range {
+        Some((min, max)) => Some((min.min(amount), max.max(amount))),
+        None => Some((amount, amount)),
+    }).unwrap_or((0, 0))
+}
@@ -0,0 +1,356 @@
+use std::{io::{self, Write}, process};
+
+use fish_core::{error::Result, game::{Bot, BotGame, Move, Query}, neural_network::{Activation, NeuralNetwork}, observer::GameObserver, player::{GoFishPlayer, Observation, PlayerView}, probability::Probability, rating::Ratings, rules::RuleSet, util};
+
+const USAGE: &str = "Usage: play [--players N] [--seat N] [--seed N] [--beliefs] [--ratings FILE] [--name NAME] [NETWORK...]
+
+Plays Go Fish against bots. The bots use the networks saved by training in order, repeating the last one,
+or untrained networks if none are given.
+With a ratings file, the game is rated with you as NAME and the bots by their file names.";
+
+const HELP: &str = "Commands:
+  ask <player> <rank>  Ask a player for a rank, e.g. `ask 2 queens`
+  hand                 Show your hand and the table
+  beliefs              Show what you could know about every other hand
+  help                 Show this help
+  quit                 Leave the game";
+
+/// The names of the ranks of a standard deck as plural, singular and short name
+const RANK_NAMES: [(&str, &str, &str); 13] = [
+    ("twos", "two", "2"), ("threes", "three", "3"), ("fours", "four", "4"), ("fives", "five", "5"),
+    ("sixes", "six", "6"), ("sevens", "seven", "7"), ("eights", "eight", "8"), ("nines", "nine", "9"),
+    ("tens", "ten", "10"), ("jacks", "jack", "j"), ("queens", "queen", "q"), ("kings", "king", "k"),
+    ("aces", "ace", "a"),
+];
+
+struct Options {
+    players: usize,
+    seat: usize,
+    seed: u64,
+    beliefs: bool,
+    ratings: Option<String>,
+    name: String,
+    networks: Vec<String>,
+}
+
+/// A player sitting at the terminal
+#[derive(Clone)]
+struct Human {
+    observer: GameObserver,
+    /// Whether to show the belief table before every move
+    beliefs: bool,
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|err| {
+        eprintln!("{err}\n\n{USAGE}");
+        process::exit(2);
+    });
+
+    if let Err(err) = play(options) {
+        eprintln!("{err}");
+        process::exit(1);
+    }
+}
+
+fn parse_args() -> std::result::Result<Options, String> {
+    let mut options = Options {
+        players: 3,
+        seat: 0,
+        seed: rand::random(),
+        beliefs: false,
+        ratings: None,
+        name: "human".to_string(),
+        networks: Vec::new(),
+    };
+
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next()
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| format!("{name} needs a number"));
+
+        match arg.as_str() {
+            "--players" => options.players = value("--players")? as usize,
+            "--seat" => options.seat = value("--seat")? as usize,
+            "--seed" => options.seed = value("--seed")?,
+            "--beliefs" => options.beliefs = true,
+            "--ratings" => options.ratings = Some(args.next().ok_or("--ratings needs a file")?),
+            "--name" => options.name = args.next().ok_or("--name needs a name")?,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                process::exit(0);
+            },
+            _ if arg.starts_with("--") => return Err(format!("Unknown option {arg}")),
+            _ => options.networks.push(arg),
+        }
+    }
+
+    if options.players < 2 || options.seat >= options.players {
+        return Err(format!("Seat {} doesn't exist in a game of {} players", options.seat, options.players));
+    }
+
+    Ok(options)
+}
+
+fn play(options: Options) -> Result<()> {
+    let rules = RuleSet::default();
+    let mut rng = util::rng_from_seed(options.seed);
+
+    let networks = if options.networks.is_empty() {
+        println!("No networks given, so the bots are untrained.");
+
+        vec![NeuralNetwork::new_rand(
+            Bot::inputs(options.players, &rules),
+            &[
+                (Bot::outputs(options.players, &rules), Activation::Tanh),
+                (Bot::outputs(options.players, &rules), Activation::Softmax),
+            ],
+            &mut rng
+        )]
+    } else {
+        options.networks.iter().map(NeuralNetwork::load).collect::<Result<Vec<_>>>()?
+    };
+
+    if let Some(network) = networks.iter().find(|network| network.inputs() != Bot::inputs(options.players, &rules)) {
+        eprintln!("A network with {} inputs can't play in a game of {} players", network.inputs(), options.players);
+        process::exit(1);
+    }
+
+    println!("Seed: {}\nYou are P{}. Type `help` for the commands.\n", options.seed, options.seat);
+
+    let mut bots = 0;
+    let mut game = BotGame::new_rand_with(options.players, &rules, options.seed, |observer| {
+        if observer.id == options.seat {
+            return Box::new(Human { observer, beliefs: options.beliefs });
+        }
+
+        let network = &networks[bots.min(networks.len() - 1)];
+        bots += 1;
+
+        Box::new(Bot::new(observer, network))
+    });
+
+    // Bots which can only ask each other for cards nobody has left can go on forever
+    for _ in 0..10_000 {
+        if game.over {
+            break;
+        }
+
+        game.step()?;
+    }
+
+    if !game.over {
+        println!("\nThe bots are stuck asking for cards nobody has, so the game ends here.");
+    }
+
+    let standings = game.standings();
+    println!("\nBooks: {}", (0..options.players).map(|seat| format!("{} {}", seat_name(seat, options.seat), standings.books[seat])).collect::<Vec<_>>().join(", "));
+
+    match standings.winners().as_slice() {
+        [winner] if *winner == options.seat => println!("You win!"),
+        [winner] => println!("P{winner} wins."),
+        winners if winners.contains(&options.seat) => println!("You share first place."),
+        _ => println!("First place is shared."),
+    }
+
+    if let Some(path) = &options.ratings {
+        let mut ratings = Ratings::load(path)?;
+        let names = (0..options.players).map(|seat| match seat.cmp(&options.seat) {
+            std::cmp::Ordering::Equal => options.name.clone(),
+            // The bots are seated in the order of the networks
+            order => network_name(&options.networks, seat - (order == std::cmp::Ordering::Greater) as usize),
+        }).collect::<Vec<_>>();
+
+        let changes = ratings.update(&names, &standings);
+        ratings.save(path)?;
+
+        for (name, change) in names.iter().zip(changes).filter(|(name, _)| **name == options.name) {
+            println!("Your rating: {:.0} ({change:+.0})", ratings.get(name).elo);
+        }
+    }
+
+    Ok(())
+}
+
+impl GoFishPlayer for Human {
+    fn choose_move(&mut self, view: &PlayerView) -> Result<Option<Move>> {
+        let legal_moves = view.legal_moves();
+
+        if legal_moves.is_empty() {
+            return Ok(None);
+        }
+
+        if legal_moves == [Move::Pickup] {
+            println!("Your hand is empty, so you pick up.");
+            return Ok(Some(Move::Pickup));
+        }
+
+        println!();
+        self.print_table(view);
+
+        if self.beliefs {
+            self.print_beliefs();
+        }
+
+        loop {
+            print!("> ");
+            io::stdout().flush()?;
+
+            let mut line = String::new();
+
+            if io::stdin().read_line(&mut line)? == 0 {
+                process::exit(0);
+            }
+
+            let words = line.split_whitespace().collect::<Vec<_>>();
+
+            match words.as_slice() {
+                ["ask", player, rank] => {
+                    let (Some(player), Some(card)) = (parse_seat(player), parse_rank(rank, view.rules.ranks)) else {
+                        println!("Ask like `ask 2 queens`");
+                        continue;
+                    };
+
+                    let m = Move::Query(Query { player, card });
+
+                    if view.is_legal(&m) {
+                        return Ok(Some(m));
+                    }
+
+                    println!("You can only ask the other players for ranks you hold");
+                },
+                ["hand"] => self.print_table(view),
+                ["beliefs"] => self.print_beliefs(),
+                ["help"] => println!("{HELP}"),
+                ["quit"] => process::exit(0),
+                [] => {},
+                _ => println!("Unknown command. {HELP}"),
+            }
+        }
+    }
+
+    fn observe(&mut self, observation: &Observation) -> Result<()> {
+        let me = self.observer.id;
+        let ranks = self.observer.rules.ranks;
+
+        match *observation {
+            Observation::Pickup { player, card: Some(card) } if player == me => println!("You drew {}", with_article(&rank_name(card, ranks, 1))),
+            Observation::Pickup { player, .. } => println!("P{player} drew a card"),
+            Observation::Query { player, target, card, amount, books } => {
+                let outcome = if amount > 0 { format!("got {amount}") } else { "go fish".to_string() };
+
+                println!("{} asked {} for {}: {outcome}{}", seat_name(player, me), if target == me { "you".to_string() } else { format!("P{target}") }, rank_name(card, ranks, 2), books_text(books));
+            },
+            Observation::Place { player, card, books } => println!("{} placed {} of {}", seat_name(player, me), books_text(books).trim_start_matches(", "), rank_name(card, ranks, 2)),
+        }
+
+        self.observer.observe(observation)
+    }
+
+    fn observer(&self) -> Option<&GameObserver> {
+        Some(&self.observer)
+    }
+}
+
+impl Human {
+    /// Prints the own hand and what everyone can see
+    fn print_table(&self, view: &PlayerView) {
+        let hand = view.hand.iter()
+            .enumerate()
+            .filter(|(_, &amount)| amount > 0)
+            .map(|(rank, &amount)| format!("{amount} {}", rank_name(rank, view.rules.ranks, amount)))
+            .collect::<Vec<_>>();
+
+        println!("Your hand: {}", hand.join(", "));
+
+        for seat in (0..view.hand_sizes.len()).filter(|&seat| seat != view.seat) {
+            println!("P{seat}: {} cards, {} books", view.hand_sizes[seat], view.books[seat]);
+        }
+
+        println!("Deck: {} cards, your books: {}", view.deck_size, view.books[view.seat]);
+    }
+
+    /// Prints what the observer knows about every other hand and the deck
+    fn print_beliefs(&self) {
+        let ranks = self.observer.rules.ranks;
+        let mut columns = (0..self.observer.other_players.len()).map(|i| format!("P{}", self.observer.seat_id(i))).collect::<Vec<_>>();
+        columns.push("Deck".to_string());
+
+        println!("{:>8} {}", "", columns.iter().map(|column| format!("{column:>6}")).collect::<String>());
+
+        for rank in 0..ranks {
+            let cells = self.observer.other_players.iter()
+                .map(|player| &player.cards)
+                .chain([&self.observer.deck])
+                .map(|deck| format!("{:>6}", probability_text(deck.cards[rank])))
+                .collect::<String>();
+
+            println!("{:>8} {cells}", rank_name(rank, ranks, 2));
+        }
+    }
+}
+
+/// Names the bot with the given index by the file of its network
+fn network_name(networks: &[String], bot: usize) -> String {
+    match networks.get(bot.min(networks.len().max(1) - 1)) {
+        Some(path) => std::path::Path::new(path).file_stem().map_or_else(|| path.clone(), |stem| stem.to_string_lossy().into_owned()),
+        None => "untrained".to_string(),
+    }
+}
+
+fn seat_name(seat: usize, me: usize) -> String {
+    if seat == me {
+        "You".to_string()
+    } else {
+        format!("P{seat}")
+    }
+}
+
+/// Names a rank, using the names of a standard deck if it has 13 ranks
+fn rank_name(rank: usize, ranks: usize, amount: usize) -> String {
+    match RANK_NAMES.get(rank) {
+        Some(&(plural, _, _)) if ranks == RANK_NAMES.len() && amount != 1 => plural.to_string(),
+        Some(&(_, singular, _)) if ranks == RANK_NAMES.len() => singular.to_string(),
+        _ => rank.to_string(),
+    }
+}
+
+fn with_article(name: &str) -> String {
+    if name.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        format!("an {name}")
+    } else {
+        format!("a {name}")
+    }
+}
+
+fn parse_rank(rank: &str, ranks: usize) -> Option<usize> {
+    let rank = rank.to_lowercase();
+
+    if ranks == RANK_NAMES.len() {
+        return RANK_NAMES.iter().position(|&(plural, singular, short)| [plural, singular, short].contains(&rank.as_str()));
+    }
+
+    rank.parse().ok().filter(|&rank| rank < ranks)
+}
+
+fn parse_seat(seat: &str) -> Option<usize> {
+    seat.trim_start_matches(['P', 'p']).parse().ok()
+}
+
+fn books_text(books: usize) -> String {
+    match books {
+        0 => String::new(),
+        1 => ", a book".to_string(),
+        books => format!(", {books} books"),
+    }
+}
+
+fn probability_text(probability: Probability) -> String {
+    match probability {
+        Probability::Known(x) => x.to_string(),
+        Probability::MoreThan(x) => format!(">={x}"),
+        Probability::Unknown(x) => format!("~{x:.1}"),
+    }
+}
@@ -0,0 +1,127 @@
+use std::{path::Path, process};
+
+use fish_core::{baseline, error::Result, game::Bot, neural_network::NeuralNetwork, oracle, rating::Ratings, rules::RuleSet, tournament::{self, Tournament}};
+
+const USAGE: &str = "Usage: tournament [--players N] [--deals N] [--seed N] [--ratings FILE] [--baselines] [--oracle] [NETWORK...]
+
+Plays a round robin between saved networks. Every group of players plays the same deals from every seat,
+and the cross table shows how often the entrant of each row placed ahead of the entrant of each column.
+With --baselines, the hand-written baseline bots take part as well. With --oracle, a bot which sees every hand takes
+part and every decision is compared with what it would have played.
+The entrants are rated by their file names, which have to differ from each other and from the bots taking part,
+continuing and updating the ratings in FILE if one is given.";
+
+struct Options {
+    players: usize,
+    deals: usize,
+    seed: u64,
+    ratings: Option<String>,
+    baselines: bool,
+    oracle: bool,
+    networks: Vec<String>,
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|err| {
+        eprintln!("{err}\n\n{USAGE}");
+        process::exit(2);
+    });
+
+    if let Err(err) = run(options) {
+        eprintln!("{err}");
+        process::exit(1);
+    }
+}
+
+fn parse_args() -> std::result::Result<Options, String> {
+    let mut options = Options {
+        players: 2,
+        deals: 50,
+        seed: 0,
+        ratings: None,
+        baselines: false,
+        oracle: false,
+        networks: Vec::new(),
+    };
+
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next()
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| format!("{name} needs a number"));
+
+        match arg.as_str() {
+            "--players" => options.players = value("--players")? as usize,
+            "--deals" => options.deals = value("--deals")? as usize,
+            "--seed" => options.seed = value("--seed")?,
+            "--ratings" => options.ratings = Some(args.next().ok_or("--ratings needs a file")?),
+            "--baselines" => options.baselines = true,
+            "--oracle" => options.oracle = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                process::exit(0);
+            },
+            _ if arg.starts_with("--") => return Err(format!("Unknown option {arg}")),
+            _ => options.networks.push(arg),
+        }
+    }
+
+    let entrants = options.networks.len() + if options.baselines { baseline::entrants().len() } else { 0 } + options.oracle as usize;
+
+    if options.players < 2 || entrants < options.players {
+        return Err(format!("A game of {} players needs at least as many entrants", options.players.max(2)));
+    }
+
+    Ok(options)
+}
+
+fn run(options: Options) -> Result<()> {
+    let rules = RuleSet::default();
+    let networks = options.networks.iter().map(NeuralNetwork::load).collect::<Result<Vec<_>>>()?;
+
+    if let Some(network) = networks.iter().find(|network| network.inputs() != Bot::inputs(options.players, &rules)) {
+        eprintln!("A network with {} inputs can't play in a game of {} players", network.inputs(), options.players);
+        process::exit(1);
+    }
+
+    let mut entrants = options.networks.iter()
+        .zip(&networks)
+        .map(|(path, network)| {
+            let name = Path::new(path).file_stem().map_or_else(|| path.clone(), |stem| stem.to_string_lossy().into_owned());
+            (name, tournament::bot_entrant(network))
+        })
+        .collect::<Vec<_>>();
+
+    if options.baselines {
+        entrants.extend(baseline::entrants());
+    }
+
+    if options.oracle {
+        entrants.push(oracle::entrant());
+    }
+
+    if let Some((name, _)) = entrants.iter().enumerate().find_map(|(i, entrant)| entrants[..i].iter().any(|(other, _)| *other == entrant.0).then_some(entrant)) {
+        eprintln!("Two entrants are named {name}, which the ratings can't tell apart");
+        process::exit(1);
+    }
+
+    println!("Seed: {}, {} deals per group\n", options.seed, options.deals);
+
+    let mut ratings = match &options.ratings {
+        Some(path) => Ratings::load(path)?,
+        None => Ratings::default(),
+    };
+
+    let mut tournament = Tournament::new(rules, options.players, options.deals, 10_000, options.seed);
+    tournament.oracle_agreement = options.oracle;
+
+    let result = tournament.run(&entrants, &mut ratings)?;
+    print!("{result}");
+
+    if let Some(path) = &options.ratings {
+        ratings.save(path)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Serialize, Deserialize};
+use crate::{observer::KnownDeck, rules::RuleSet};
+
+/// A physical card
+///
+/// Most of the game only cares about the rank of a card, which is what the `card` of a [`KnownDeck`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Card {
+    pub rank: usize,
+    pub suit: usize,
+}
+
+impl Card {
+    pub fn new(rank: usize, suit: usize) -> Self {
+        Self {
+            rank,
+            suit,
+        }
+    }
+
+    /// Returns every card of the deck described by `rules`, ordered by rank and then by suit
+    pub fn full_deck(rules: &RuleSet) -> Vec<Card> {
+        (0..rules.ranks).flat_map(|rank| (0..rules.suits).map(move |suit| Card::new(rank, suit))).collect()
+    }
+}
+
+const SUIT_NAMES: [char; 4] = ['C', 'D', 'H', 'S'];
+
+impl fmt::Display for Card {
+    /// Writes the rank followed by the suit, e.g. `7H`
+    ///
+    /// Suits past the fourth belong to additional decks, which repeat the same suits followed by the number of the
+    /// deck, e.g. `7H2`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.rank, SUIT_NAMES[self.suit % SUIT_NAMES.len()])?;
+
+        if self.suit >= SUIT_NAMES.len() {
+            write!(f, "{}", self.suit / SUIT_NAMES.len() + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Card {
+    type Err = String;
+
+    /// Parses a card written by its [`Display`](fmt::Display) implementation
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid card {s:?}");
+
+        let suit_start = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+        let (rank, rest) = s.split_at(suit_start);
+        let mut rest = rest.chars();
+
+        let name = rest.next().ok_or_else(invalid)?;
+        let suit = SUIT_NAMES.iter().position(|&suit| suit == name).ok_or_else(invalid)?;
+        let deck = match rest.as_str() {
+            "" => 1,
+            deck => deck.parse::<usize>().ok().filter(|&deck| deck >= 2).ok_or_else(invalid)?,
+        };
+
+        Ok(Card::new(rank.parse().map_err(|_| invalid())?, suit + (deck - 1) * SUIT_NAMES.len()))
+    }
+}
+
+/// Counts how many cards of each of `ranks` ranks there are
+pub fn rank_counts(cards: &[Card], ranks: usize) -> KnownDeck {
+    let mut counts = vec![0; ranks];
+
+    for card in cards {
+        counts[card.rank] += 1;
+    }
+
+    counts
+}
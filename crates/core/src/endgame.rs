@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use crate::{error::{GoFishError, Result}, game::{Move, Query}, observer::{GameObserver, KnownDeck, World}, player::{GoFishPlayer, Observation, PlayerView}, result::GameResult, rules::RuleSet, state::GameState};
+
+/// A position once the deck is empty, where nothing is left to chance
+///
+/// Only the amount of each rank in every hand matters, as the suits can't change how the game goes on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Endgame {
+    pub hands: Vec<KnownDeck>,
+    /// The amount of sets each seat has placed
+    pub books: Vec<usize>,
+    pub current_player: usize,
+    /// The failed asks in a row since cards last changed hands
+    pub passes: usize,
+}
+
+/// The most positions an [`EndgameSolver`] goes through for a single solve or analysis by default
+pub const MAX_POSITIONS: usize = 20_000;
+
+/// Solves endgames where every hand is known
+///
+/// Every legal ask is searched, including the ones which fail. With the deck empty, a failed ask only passes the turn,
+/// so once every player holding cards has failed in a row the position would repeat. This is assumed never to happen,
+/// which makes the last of them take cards, so the game always ends. Each player plays to finish ahead of as many
+/// others as possible. Solved positions are remembered, so a solver should be kept for a whole game.
+///
+/// The number of positions grows quickly with the cards left in the hands of more than two players, so a search
+/// which goes through more than `max_positions` of them fails with [`GoFishError::SearchLimit`]. The remembered
+/// positions are forgotten once there are more of them than that.
+#[derive(Clone)]
+pub struct EndgameSolver {
+    pub rules: RuleSet,
+    pub max_positions: usize,
+    solved: HashMap<Endgame, Vec<usize>>,
+    /// The positions the current search has gone through so far
+    searched: usize,
+}
+
+/// The value of a position as far as the observing player can tell
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    /// The seat of the observing player
+    pub seat: usize,
+    /// The number of different deals of the unseen cards which agree with the observations
+    pub worlds: usize,
+    /// The expected final sets of each seat with best play
+    pub books: Vec<f32>,
+    /// The expected share of the other seats each seat finishes ahead of with best play, counting ties as half
+    pub value: Vec<f32>,
+    /// The value of each seat after each legal move, if it is the observing player's turn
+    pub moves: Vec<(Move, Vec<f32>)>,
+}
+
+/// Switches from another player to the [`EndgameSolver`] once the deck is empty and few enough deals are possible
+#[derive(Clone)]
+pub struct EndgameBot {
+    pub player: Box<dyn GoFishPlayer>,
+    pub observer: GameObserver,
+    /// The most deals of the unseen cards the solver goes through before leaving the move to `player`
+    pub max_worlds: usize,
+    solver: EndgameSolver,
+}
+
+impl Endgame {
+    /// Takes the position from the actual cards of a game
+    pub fn from_state(state: &GameState, current_player: usize, rules: &RuleSet) -> Self {
+        Self {
+            hands: (0..state.hands.len()).map(|seat| state.hand(seat)).collect(),
+            books: state.books(rules),
+            current_player,
+            passes: 0,
+        }
+    }
+
+    /// Whether every card has been placed or nobody has any cards left
+    pub fn is_over(&self) -> bool {
+        self.hands.iter().all(|hand| hand.iter().all(|&amount| amount == 0))
+    }
+
+    /// Every legal ask of the current player
+    pub fn queries(&self) -> Vec<Query> {
+        let seat = self.current_player;
+
+        (0..self.hands.len())
+            .filter(|&player| player != seat)
+            .flat_map(|player| (0..self.hands[seat].len()).map(move |card| Query { player, card }))
+            .filter(|query| self.hands[seat][query.card] > 0)
+            .collect()
+    }
+
+    /// Every ask of the current player which leads to a different position
+    ///
+    /// All failed asks lead to the same position, so only the first one is kept. It is left out once every other
+    /// player holding cards has failed in a row, as it would repeat the position from before them.
+    pub fn distinct_queries(&self) -> Vec<Query> {
+        let holders = self.hands.iter().filter(|hand| hand.iter().any(|&amount| amount > 0)).count();
+        let (successful, failed): (Vec<_>, Vec<_>) = self.queries().into_iter().partition(|query| self.hands[query.player][query.card] > 0);
+
+        successful.into_iter()
+            .chain(failed.into_iter().take(1).filter(|_| self.passes + 1 < holders))
+            .collect()
+    }
+
+    /// Plays a query of the current player, which makes them go fish in an empty deck if it fails
+    pub fn play(&self, query: Query, rules: &RuleSet) -> Self {
+        let mut position = self.clone();
+        let seat = self.current_player;
+        let amount = position.hands[query.player][query.card];
+
+        position.hands[query.player][query.card] = 0;
+        position.hands[seat][query.card] += amount;
+        position.passes = if amount == 0 { self.passes + 1 } else { 0 };
+
+        let hand = &mut position.hands[seat][query.card];
+        position.books[seat] += *hand / rules.book_size;
+        *hand %= rules.book_size;
+
+        if amount == 0 || !rules.continue_on_success || position.hands[seat].iter().all(|&amount| amount == 0) {
+            position.pass_turn();
+        }
+
+        position
+    }
+
+    /// Passes the turn on to the next player holding cards, as nobody else can move
+    fn pass_turn(&mut self) {
+        let players = self.hands.len();
+
+        if let Some(next) = (1..=players).map(|i| (self.current_player + i) % players).find(|&seat| self.hands[seat].iter().any(|&amount| amount > 0)) {
+            self.current_player = next;
+        }
+    }
+}
+
+impl EndgameSolver {
+    pub fn new(rules: &RuleSet) -> Self {
+        Self {
+            rules: rules.clone(),
+            max_positions: MAX_POSITIONS,
+            solved: HashMap::new(),
+            searched: 0,
+        }
+    }
+
+    /// The final sets of every seat with best play
+    pub fn solve(&mut self, position: &Endgame) -> Result<Vec<usize>> {
+        self.start_search();
+        self.search(position)
+    }
+
+    /// The best query of the current player together with the final sets it leads to
+    pub fn best_query(&mut self, position: &Endgame) -> Result<(Query, Vec<usize>)> {
+        self.start_search();
+        self.search_queries(position)
+    }
+
+    fn start_search(&mut self) {
+        if self.solved.len() > self.max_positions {
+            self.solved.clear();
+        }
+
+        self.searched = 0;
+    }
+
+    fn search(&mut self, position: &Endgame) -> Result<Vec<usize>> {
+        if position.is_over() {
+            return Ok(position.books.clone());
+        }
+
+        if let Some(books) = self.solved.get(position) {
+            return Ok(books.clone());
+        }
+
+        if position.hands[position.current_player].iter().all(|&amount| amount == 0) {
+            let mut position = position.clone();
+            position.pass_turn();
+
+            return self.search(&position);
+        }
+
+        self.searched += 1;
+
+        if self.searched > self.max_positions {
+            return Err(GoFishError::SearchLimit(self.max_positions));
+        }
+
+        let (_, books) = self.search_queries(position)?;
+        self.solved.insert(position.clone(), books.clone());
+
+        Ok(books)
+    }
+
+    fn search_queries(&mut self, position: &Endgame) -> Result<(Query, Vec<usize>)> {
+        let seat = position.current_player;
+        let mut best: Option<(Query, Vec<usize>, (f32, usize))> = None;
+
+        for query in position.distinct_queries() {
+            let books = self.search(&position.play(query, &self.rules))?;
+            let rating = (GameResult::from_books(books.clone()).scores()[seat], books[seat]);
+
+            if best.as_ref().is_none_or(|(_, _, best)| rating > *best) {
+                best = Some((query, books, rating));
+            }
+        }
+
+        best.map(|(query, books, _)| (query, books))
+            .ok_or_else(|| GoFishError::InvalidState(format!("Seat {seat} holds cards nobody else has in an empty deck")))
+    }
+
+    /// Analyzes the position of an observer once the deck is empty, going through every deal of the cards it can't
+    /// see which agrees with what it has observed
+    ///
+    /// The value is exact when the observer knows every hand. Otherwise every deal is solved as if everyone could
+    /// see all cards from the next move on, so the turns of the other players are played with perfect information,
+    /// as are the later turns of the observer. Returns `None` if there are more than `max_worlds` deals or solving
+    /// them goes through more than `max_positions` positions altogether.
+    pub fn analyze(&mut self, observer: &GameObserver, books: &[usize], current_player: usize, max_worlds: usize) -> Result<Option<Analysis>> {
+        if observer.deck.size > 0 {
+            return Err(GoFishError::InvalidState("Endgames can only be analyzed once the deck is empty".to_string()));
+        }
+
+        let Some(own_deck) = &observer.own_deck else {
+            return Err(GoFishError::InvalidState("Endgames can only be analyzed by a player".to_string()));
+        };
+
+        let Some(worlds) = observer.worlds(max_worlds)? else {
+            return Ok(None);
+        };
+
+        self.start_search();
+
+        match self.analyze_worlds(observer, own_deck, &worlds, books, current_player) {
+            Err(GoFishError::SearchLimit(_)) => Ok(None),
+            analysis => analysis.map(Some),
+        }
+    }
+
+    fn analyze_worlds(&mut self, observer: &GameObserver, own_deck: &KnownDeck, worlds: &[(World, f64)], books: &[usize], current_player: usize) -> Result<Analysis> {
+        let players = books.len();
+        let mut analysis = Analysis {
+            seat: observer.id,
+            worlds: worlds.len(),
+            books: vec![0.; players],
+            value: vec![0.; players],
+            moves: Vec::new(),
+        };
+
+        let legal_queries = if current_player == observer.id {
+            (0..players)
+                .filter(|&player| player != observer.id)
+                .flat_map(|player| (0..own_deck.len()).filter(|&card| own_deck[card] > 0).map(move |card| Query { player, card }))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut moves = legal_queries.iter().map(|&query| (Move::Query(query), vec![0.; players])).collect::<Vec<_>>();
+
+        for (world, weight) in worlds {
+            let weight = *weight as f32;
+            let mut hands = world.hands.clone();
+            hands.insert(observer.id, own_deck.clone());
+
+            let position = Endgame { hands, books: books.to_vec(), current_player, passes: 0 };
+
+            for (i, &query) in legal_queries.iter().enumerate() {
+                let scores = GameResult::from_books(self.search(&position.play(query, &self.rules))?).scores();
+
+                for (value, score) in moves[i].1.iter_mut().zip(scores) {
+                    *value += weight * score;
+                }
+            }
+
+            if legal_queries.is_empty() {
+                let final_books = self.search(&position)?;
+
+                for (seat, score) in GameResult::from_books(final_books.clone()).scores().into_iter().enumerate() {
+                    analysis.value[seat] += weight * score;
+                    analysis.books[seat] += weight * final_books[seat] as f32;
+                }
+            }
+        }
+
+        // The observing player picks the move which is best on average, after which its sets are expected
+        if let Some((best, value)) = moves.iter().max_by(|a, b| a.1[observer.id].total_cmp(&b.1[observer.id])) {
+            analysis.value = value.clone();
+
+            let Move::Query(best) = *best else {
+                unreachable!("Only queries are analyzed");
+            };
+
+            for (world, weight) in worlds {
+                let mut hands = world.hands.clone();
+                hands.insert(observer.id, own_deck.clone());
+
+                let final_books = self.search(&Endgame { hands, books: books.to_vec(), current_player, passes: 0 }.play(best, &self.rules))?;
+
+                for (seat, books) in final_books.into_iter().enumerate() {
+                    analysis.books[seat] += *weight as f32 * books as f32;
+                }
+            }
+        }
+
+        analysis.moves = moves;
+
+        Ok(analysis)
+    }
+}
+
+impl Analysis {
+    /// The move with the highest value for the observing player, if it is their turn
+    pub fn best_move(&self) -> Option<Move> {
+        self.moves.iter()
+            .max_by(|a, b| a.1[self.seat].total_cmp(&b.1[self.seat]))
+            .map(|(m, _)| *m)
+    }
+}
+
+impl EndgameBot {
+    pub fn new(player: Box<dyn GoFishPlayer>, observer: GameObserver, max_worlds: usize) -> Self {
+        Self {
+            solver: EndgameSolver::new(&observer.rules),
+            player,
+            observer,
+            max_worlds,
+        }
+    }
+}
+
+impl GoFishPlayer for EndgameBot {
+    fn choose_move(&mut self, view: &PlayerView) -> Result<Option<Move>> {
+        if view.deck_size == 0 && view.legal_moves().len() > 1 {
+            // The estimates of the observer can contradict each other, in which case there is nothing to solve
+            match self.solver.analyze(&self.observer, &view.books, view.seat, self.max_worlds) {
+                Ok(analysis) => if let Some(m) = analysis.and_then(|analysis| analysis.best_move()) {
+                    return Ok(Some(m));
+                },
+                Err(GoFishError::InconsistentObservation(_)) => {},
+                Err(err) => return Err(err),
+            }
+        }
+
+        self.player.choose_move(view)
+    }
+
+    fn observe(&mut self, observation: &Observation) -> Result<()> {
+        self.player.observe(observation)?;
+        self.observer.observe(observation)
+    }
+
+    fn observer(&self) -> Option<&GameObserver> {
+        Some(&self.observer)
+    }
+}
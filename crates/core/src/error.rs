@@ -0,0 +1,77 @@
+use std::{fmt, io};
+
+use crate::game::IllegalMove;
+
+/// A [`Result`](std::result::Result) which fails with a [`GoFishError`]
+pub type Result<T, E = GoFishError> = std::result::Result<T, E>;
+
+/// Everything which can go wrong while playing, observing or recording a game
+#[derive(Debug)]
+pub enum GoFishError {
+    /// A player tried to play a move they aren't allowed to
+    IllegalMove(IllegalMove),
+    /// An observer was told something which contradicts what it already knows
+    InconsistentObservation(String),
+    /// The cards of a game, or what an observer knows about them, broke one of the rules
+    InvalidState(String),
+    /// A network was given a different amount of inputs than it was built for
+    ShapeMismatch { expected: usize, actual: usize },
+    /// A network without any layers was evaluated or loaded
+    EmptyNetwork,
+    /// A search would have gone through more than this many positions
+    SearchLimit(usize),
+    /// A replay doesn't contain the starting hand of a seat
+    MissingDeal(usize),
+    /// A line of a game written in [notation](crate::notation) couldn't be read or doesn't match the game
+    Notation { line: usize, reason: String },
+    /// A file, e.g. a saved network, replay or ratings, couldn't be read or written
+    Io(io::Error),
+    /// A saved network, replay or ratings aren't valid JSON of the expected shape
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for GoFishError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoFishError::IllegalMove(illegal_move) => write!(f, "Illegal move: {illegal_move}"),
+            GoFishError::InconsistentObservation(reason) => write!(f, "Inconsistent observation: {reason}"),
+            GoFishError::InvalidState(reason) => write!(f, "Invalid game state: {reason}"),
+            GoFishError::ShapeMismatch { expected, actual } => write!(f, "The network expects {expected} inputs but got {actual}"),
+            GoFishError::EmptyNetwork => write!(f, "The network has no layers"),
+            GoFishError::SearchLimit(positions) => write!(f, "The search would go through more than {positions} positions"),
+            GoFishError::MissingDeal(seat) => write!(f, "Seat {seat} was never dealt a hand"),
+            GoFishError::Notation { line, reason } => write!(f, "Line {line}: {reason}"),
+            GoFishError::Io(err) => write!(f, "{err}"),
+            GoFishError::Json(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for GoFishError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GoFishError::IllegalMove(illegal_move) => Some(illegal_move),
+            GoFishError::Io(err) => Some(err),
+            GoFishError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<IllegalMove> for GoFishError {
+    fn from(illegal_move: IllegalMove) -> Self {
+        GoFishError::IllegalMove(illegal_move)
+    }
+}
+
+impl From<io::Error> for GoFishError {
+    fn from(err: io::Error) -> Self {
+        GoFishError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for GoFishError {
+    fn from(err: serde_json::Error) -> Self {
+        GoFishError::Json(err)
+    }
+}
@@ -0,0 +1,22 @@
+use serde::{Serialize, Deserialize};
+use crate::{card::Card, result::GameResult};
+
+/// Something which happened in a game, as seen by someone who knows every card
+///
+/// Players are referred to by their seat.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum GameEvent {
+    /// A seat was dealt its starting hand
+    Deal { seat: usize, hand: Vec<Card> },
+    /// A player asked another player for a card
+    Ask { player: usize, target: usize, card: usize },
+    /// A player gave all of their cards of a rank to the player who asked for them
+    Give { player: usize, target: usize, cards: Vec<Card> },
+    /// A player picked up a card from the deck
+    Draw { player: usize, card: Card },
+    /// A player placed sets of a rank
+    Place { player: usize, cards: Vec<Card> },
+    /// The game ended
+    GameOver { result: GameResult },
+}
@@ -1,138 +1,475 @@
-use anyhow::anyhow;
-use anyhow::Result;
-use rand::Rng;
-use crate::{observer::{GameObserver, KnownDeck, DECK_SIZE, STARTING_CARDS}, neural_network::NeuralNetwork, probability::Probability, util::lerp};
+use std::fmt;
+
+use rand::seq::SliceRandom;
+use crate::{card::{self, Card}, error::{GoFishError, Result}, event::GameEvent, observer::GameObserver, neural_network::NeuralNetwork, player::{GoFishPlayer, Observation, PlayerView}, probability::Probability, replay::Replay, result::GameResult, rules::{RuleSet, EmptyHandRule}, state::GameState, stats::GameStats, util::{self, lerp}};
 
 pub const INPUTS_PER_UNKNOWN_CARD: usize = 2;
 
+/// A game of Go Fish between [`GoFishPlayer`]s
+///
+/// Cloning a game copies every player including what they have observed. The order of the remaining deck is part
+/// of [`GameState`], so a clone continues exactly like the original.
+#[derive(Clone)]
 pub struct BotGame {
-    pub players: Vec<Bot>,
-    pub deck: KnownDeck,
+    pub players: Vec<Box<dyn GoFishPlayer>>,
+    pub state: GameState,
     pub current_player: usize,
     pub over: bool,
+    pub rules: RuleSet,
+    /// Everything which has happened in the game so far
+    pub events: Vec<GameEvent>,
+    /// The seed the deck was shuffled with, or 0 for a game started with [`BotGame::from_deal`]
+    pub seed: u64,
+}
+
+/// A position of a [`BotGame`] which can be returned to with [`BotGame::restore`]
+#[derive(Clone)]
+pub struct GameSnapshot {
+    players: Vec<Box<dyn GoFishPlayer>>,
+    state: GameState,
+    current_player: usize,
+    over: bool,
+    events: usize,
+}
+
+/// What happened when a move was played
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Outcome {
+    /// The cards the player received from the player they asked
+    pub received: Vec<Card>,
+    /// The cards the player picked up from the deck
+    pub drawn: Vec<Card>,
+    /// The amount of sets the player placed
+    pub books: usize,
+    /// Whether the player gets another turn
+    pub another_turn: bool,
+}
+
+/// Why a move can't be played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IllegalMove {
+    GameOver,
+    NotYourTurn { seat: usize, current_player: usize },
+    /// Picking up is only allowed with an empty hand, unless the rules make players with empty hands sit out
+    CannotPickUp,
+    /// There are no cards left to pick up
+    EmptyStock,
+    /// The seat doesn't exist or is the player's own
+    InvalidTarget(usize),
+    /// Players can only ask for cards they hold
+    RankNotHeld(usize),
 }
 
+impl fmt::Display for IllegalMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IllegalMove::GameOver => write!(f, "The game is over"),
+            IllegalMove::NotYourTurn { seat, current_player } => write!(f, "Seat {seat} moved during the turn of seat {current_player}"),
+            IllegalMove::CannotPickUp => write!(f, "Picking up is only allowed with an empty hand"),
+            IllegalMove::EmptyStock => write!(f, "The deck is empty"),
+            IllegalMove::InvalidTarget(seat) => write!(f, "Seat {seat} can't be asked"),
+            IllegalMove::RankNotHeld(card) => write!(f, "Only ranks in the hand can be asked for, not {card}"),
+        }
+    }
+}
+
+impl std::error::Error for IllegalMove {}
+
 #[derive(Clone)]
 pub struct Bot {
     pub observer: GameObserver,
     pub network: NeuralNetwork,
-    pub number_placed: usize,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Move {
     Pickup,
     Query(Query)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Query {
+    /// The seat of the player being asked
     pub player: usize,
     pub card: usize,
 }
 
 impl BotGame {
-    pub fn new_rand(networks: &[NeuralNetwork], rng: &mut impl Rng) -> Self {
-        let (bots, deck) = loop {
-            let mut deck = [4; DECK_SIZE];
-
-            let bots = networks.iter().enumerate().map(|(i, network)| {
-                    let mut player_deck = [0; DECK_SIZE];
-                    for _ in 0..STARTING_CARDS {
-                        let mut rand = rng.gen_range(0..DECK_SIZE);
-                        while deck[rand] == 0 {
-                            rand = rng.gen_range(0..DECK_SIZE);
-                        }
-        
-                        player_deck[rand] += 1;
-                        deck[rand] -= 1;
-                    }
-
-                    Bot::new(GameObserver::new(networks.len() - 1, Some(player_deck), i), network)
-                }).collect::<Vec<_>>();
-
-            // This is very lazy and inefficient, I should make it so games can start with some players already have placed cards on the table
-            // but I don't see how it could affect training and this is easier
-            if !bots.iter().any(|bot| bot.observer.own_deck.unwrap().iter().any(|&card| card == 4)) {
-                break (bots, deck);
-            }
-        };
+    /// Deals a new game between bots using `networks`
+    ///
+    /// The same seed always results in the same game.
+    pub fn new_rand(networks: &[NeuralNetwork], rules: &RuleSet, seed: u64) -> Self {
+        Self::new_rand_with(networks.len(), rules, seed, |observer| {
+            let network = &networks[observer.id];
+            Box::new(Bot::new(observer, network))
+        })
+    }
 
-        Self {
-            players: bots,
-            deck,
+    /// Deals a new game, seating the player `seat` builds from each seat's [`GameObserver`]
+    ///
+    /// Every card is equally likely to be dealt to every seat. Any sets in the starting hands are placed straight away.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rules` aren't valid, if there are fewer than 2 players or if the deck described by `rules` doesn't
+    /// have enough cards to deal every starting hand.
+    pub fn new_rand_with(players: usize, rules: &RuleSet, seed: u64, seat: impl FnMut(GameObserver) -> Box<dyn GoFishPlayer>) -> Self {
+        if let Err(err) = rules.validate() {
+            panic!("{err}");
+        }
+
+        let starting_cards = rules.starting_cards(players);
+
+        assert!(players * starting_cards <= rules.deck_size(), "A deck of {} cards is too small to deal {players} hands of {starting_cards}", rules.deck_size());
+
+        let mut deck = Card::full_deck(rules);
+        deck.shuffle(&mut util::rng_from_seed(seed));
+
+        let hands = (0..players)
+            .map(|_| deck.split_off(deck.len() - starting_cards))
+            .collect::<Vec<_>>();
+
+        let mut game = Self::from_deal(hands, deck, rules, seat).expect("A shuffled deck should make a valid deal");
+        game.seed = seed;
+
+        game
+    }
+
+    /// Starts a game from given starting hands and deck, where the last card of `stock` is on top
+    ///
+    /// There have to be at least 2 hands of the starting size given by `rules`, and together with the deck they have
+    /// to make up the whole deck. Any sets in the starting hands are placed straight away.
+    pub fn from_deal(hands: Vec<Vec<Card>>, stock: Vec<Card>, rules: &RuleSet, mut seat: impl FnMut(GameObserver) -> Box<dyn GoFishPlayer>) -> Result<Self> {
+        rules.validate()?;
+
+        let players = hands.len();
+        let starting_cards = rules.starting_cards(players);
+
+        if players < 2 {
+            return Err(GoFishError::InvalidState(format!("A game needs at least 2 players instead of {players}")));
+        }
+
+        if let Some(hand) = hands.iter().position(|hand| hand.len() != starting_cards) {
+            return Err(GoFishError::InvalidState(format!("Seat {hand} was dealt {} cards instead of {starting_cards}", hands[hand].len())));
+        }
+
+        let mut game = Self {
+            players: hands.iter().enumerate().map(|(i, hand)| seat(GameObserver::new(players - 1, Some(card::rank_counts(hand, rules.ranks)), i, rules))).collect(),
+            events: hands.iter().enumerate().map(|(seat, hand)| GameEvent::Deal { seat, hand: hand.clone() }).collect(),
+            state: GameState::new(vec![Vec::new(); players], stock, rules.ranks),
             current_player: 0,
             over: false,
+            rules: rules.clone(),
+            seed: 0,
+        };
+
+        // Place the sets which were dealt
+        for (seat, hand) in hands.iter().enumerate() {
+            for placed in game.add_to_hand(seat, hand).chunk_by(|a, b| a.rank == b.rank) {
+                game.notify(Observation::Place { player: seat, card: placed[0].rank, books: placed.len() / rules.book_size })?;
+            }
         }
+
+        game.state.validate(rules)?;
+        game.end_turn(true);
+
+        Ok(game)
     }
 
-    pub fn step(&mut self, rng: &mut impl Rng) -> Result<()> {
-        let bot_move = match self.players[self.current_player].eval() {
-            None => {
-                self.over = true;
-                return Ok(());
-            },
-            Some(m) => m
+    /// Continues a game from a position in the middle of it, with `players` in order of their seats
+    ///
+    /// Nothing is reported to the players, so they have to know the position already, and the event log starts
+    /// empty. The player in `current_player` has to be able to move unless the game is over.
+    pub fn from_state(state: GameState, current_player: usize, rules: &RuleSet, players: Vec<Box<dyn GoFishPlayer>>) -> Result<Self> {
+        if players.len() != state.hands.len() || current_player >= players.len() {
+            return Err(GoFishError::InvalidState(format!("{} players and a current player of {current_player} don't fit a game of {} seats", players.len(), state.hands.len())));
+        }
+
+        state.validate(rules)?;
+
+        let mut game = Self {
+            players,
+            state,
+            current_player,
+            over: false,
+            rules: rules.clone(),
+            events: Vec::new(),
+            seed: 0,
         };
 
-        match bot_move {
+        game.over = game.is_finished();
+
+        Ok(game)
+    }
+
+    /// Advances the game by one action of the current player
+    ///
+    /// The current player picks a move which is applied with [`BotGame::apply_move`]. A player who doesn't pick a
+    /// move passes the turn on to the next player who can move.
+    pub fn step(&mut self) -> Result<()> {
+        if self.over {
+            return Ok(());
+        }
+
+        let mut view = PlayerView::new(self.current_player, &self.state, &self.rules);
+
+        if self.players[self.current_player].sees_everything() {
+            view.truth = Some(&self.state);
+        }
+
+        match self.players[self.current_player].choose_move(&view)? {
+            None => self.end_turn(false),
+            Some(m) => {
+                self.apply_move(self.current_player, m)?;
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Makes the player in `seat` play a move
+    ///
+    /// A player with an empty hand is forced to pick up from the deck as described by [`RuleSet::empty_hand`].
+    /// A failed query makes the player "go fish" by drawing from the deck. The player gets another turn if they
+    /// drew the card they asked for, or if the query succeeded and [`RuleSet::continue_on_success`] is set.
+    /// Otherwise the turn passes on to the next player who can move. A player who can't move any more is skipped even if
+    /// they would get another turn.
+    ///
+    /// The game is over once every set has been placed or nobody can move any more.
+    ///
+    /// Fails with [`GoFishError::IllegalMove`] without changing the game if the move isn't allowed. Any other error
+    /// means that a player has lost track of the game, which leaves the move half applied.
+    pub fn apply_move(&mut self, seat: usize, player_move: Move) -> Result<Outcome> {
+        self.check_move(seat, &player_move)?;
+
+        let mut outcome = Outcome::default();
+
+        match player_move {
             Move::Pickup => {
-                let card = rand_card_from_deck(&self.deck, rng)?;
+                self.draw(&mut outcome)?;
 
-                self.players[self.current_player].observer.self_pickup(card);
+                if self.rules.empty_hand == EmptyHandRule::Refill {
+                    let starting_cards = self.rules.starting_cards(self.players.len());
 
-                if self.players[self.current_player].observer.own_deck.unwrap()[card] == 0 {
-                    self.players[self.current_player].number_placed += 1
+                    while self.state.hand_size(seat) < starting_cards && self.draw(&mut outcome)?.is_some() {}
                 }
 
-                for (i, player) in self.players.iter_mut().enumerate().filter(|(i, _)| *i != self.current_player) {
-                    player.observer.pickup(i - (i >= player.observer.id) as usize);
-                }
+                outcome.another_turn = true;
             },
             Move::Query(query) => {
-                let transfer_amount = self.players[query.player].observer.own_deck.unwrap()[query.card];
-                
-                // Inform the current player
-                self.players[self.current_player].observer.self_query(query.player, query.card, transfer_amount);
+                let cards = self.state.take_all(query.player, query.card);
+                let amount = cards.len();
 
-                let placed = self.players[self.current_player].observer.own_deck.unwrap()[query.card] == 0;
+                self.events.push(GameEvent::Ask { player: seat, target: query.player, card: query.card });
 
-                if placed {
-                    self.players[self.current_player].number_placed += 1
+                if amount > 0 {
+                    self.events.push(GameEvent::Give { player: query.player, target: seat, cards: cards.clone() });
                 }
 
-                let p = &mut self.players[query.player];
+                let books = self.add_to_hand(seat, &cards).len() / self.rules.book_size;
+                outcome.books += books;
+                outcome.received = cards;
 
-                if transfer_amount > 0 {
-                    // Inform the player who the current player asked
-                    p.observer.self_give_all(self.current_player - (self.current_player >= p.observer.id) as usize, query.card)?;
+                self.notify(Observation::Query { player: seat, target: query.player, card: query.card, amount, books })?;
 
-                    // Inform the other players not involved in the transaction
-                    for (i, player) in self.players.iter_mut().enumerate().filter(|(i, _)| *i != self.current_player && *i != query.player) {
-                        player.observer.query(self.current_player - (self.current_player >= player.observer.id) as usize, query.player - (query.player >= player.observer.id) as usize, query.card, transfer_amount, placed)?;
-                    }
-                }
+                outcome.another_turn = if amount > 0 {
+                    self.rules.continue_on_success
+                } else {
+                    // Go fish
+                    self.draw(&mut outcome)?.is_some_and(|card| card.rank == query.card)
+                };
             },
         }
 
+        #[cfg(debug_assertions)]
+        self.validate()?;
+
+        self.end_turn(outcome.another_turn);
+
+        Ok(outcome)
+    }
+
+    /// Checks whether the player in `seat` is allowed to play a move right now
+    pub fn check_move(&self, seat: usize, player_move: &Move) -> Result<(), IllegalMove> {
+        if self.over {
+            return Err(IllegalMove::GameOver);
+        }
+
+        if seat != self.current_player {
+            return Err(IllegalMove::NotYourTurn { seat, current_player: self.current_player });
+        }
+
+        let view = PlayerView::new(seat, &self.state, &self.rules);
+
+        match *player_move {
+            _ if view.is_legal(player_move) => Ok(()),
+            Move::Pickup if view.deck_size == 0 => Err(IllegalMove::EmptyStock),
+            Move::Pickup => Err(IllegalMove::CannotPickUp),
+            Move::Query(query) if query.player == seat || query.player >= self.players.len() => Err(IllegalMove::InvalidTarget(query.player)),
+            Move::Query(query) => Err(IllegalMove::RankNotHeld(query.card)),
+        }
+    }
+
+    /// Whether the game has reached its end condition
+    pub fn is_finished(&self) -> bool {
+        self.state.total_books(&self.rules) == self.rules.books()
+            || ((self.state.stock_size() == 0 || self.rules.empty_hand == EmptyHandRule::SitOut)
+                && (0..self.players.len()).all(|seat| self.state.hand_size(seat) == 0))
+    }
+
+    /// Checks the invariants of the game state and that every player's observer agrees with it
+    ///
+    /// This is done after every step in debug builds.
+    pub fn validate(&self) -> Result<()> {
+        self.state.validate(&self.rules)?;
+
+        let placed = self.state.placed_counts();
+
+        for observer in self.players.iter().filter_map(|player| player.observer()) {
+            if observer.own_deck.as_ref() != Some(&self.state.hand(observer.id)) {
+                return Err(GoFishError::InvalidState(format!("Observer {} has the wrong hand", observer.id)));
+            }
+
+            if observer.placed != placed {
+                return Err(GoFishError::InvalidState(format!("Observer {} has the wrong placed cards", observer.id)));
+            }
+
+            if observer.deck.size != self.state.stock_size() {
+                return Err(GoFishError::InvalidState(format!("Observer {} thinks the deck has {} cards instead of {}", observer.id, observer.deck.size, self.state.stock_size())));
+            }
+
+            for (i, player) in observer.other_players.iter().enumerate() {
+                let seat = observer.seat_id(i);
+
+                if player.cards.size != self.state.hand_size(seat) {
+                    return Err(GoFishError::InvalidState(format!("Observer {} thinks seat {seat} has {} cards instead of {}", observer.id, player.cards.size, self.state.hand_size(seat))));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Saves the current position, including every player's observations
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            players: self.players.clone(),
+            state: self.state.clone(),
+            current_player: self.current_player,
+            over: self.over,
+            events: self.events.len(),
+        }
+    }
+
+    /// Returns to a position saved with [`BotGame::snapshot`]
+    ///
+    /// Events which happened after the snapshot are dropped. Only the amount of events is saved, so the snapshot
+    /// has to come from this game, and restoring a snapshot invalidates every snapshot taken after it.
+    pub fn restore(&mut self, snapshot: GameSnapshot) {
+        debug_assert!(snapshot.events <= self.events.len(), "The snapshot is from after the current position");
+
+        self.players = snapshot.players;
+        self.state = snapshot.state;
+        self.current_player = snapshot.current_player;
+        self.over = snapshot.over;
+        self.events.truncate(snapshot.events);
+    }
+
+    /// Returns the current standings, regardless of whether the game is over
+    pub fn standings(&self) -> GameResult {
+        GameResult::from_books(self.state.books(&self.rules))
+    }
+
+    /// Returns the final standings, or `None` if the game is not over yet
+    pub fn result(&self) -> Option<GameResult> {
+        self.over.then(|| self.standings())
+    }
+
+    /// Collects statistics about the game so far
+    pub fn stats(&self) -> GameStats {
+        GameStats::from_events(self.players.len(), &self.events, &self.rules)
+    }
+
+    /// Records the game so far as a [`Replay`]
+    pub fn replay(&self) -> Replay {
+        Replay {
+            rules: self.rules.clone(),
+            players: self.players.len(),
+            events: self.events.clone(),
+        }
+    }
+
+    fn next_player(&mut self) {
         self.current_player += 1;
         self.current_player %= self.players.len();
+    }
+
+    /// Ends the game if it is finished, or otherwise passes the turn on unless the current player gets another one
+    ///
+    /// Everyone who can't move is skipped, and the game is over if that is everyone.
+    fn end_turn(&mut self, another_turn: bool) {
+        self.over = self.is_finished();
+
+        if !self.over {
+            if !another_turn {
+                self.next_player();
+            }
+
+            let mut skipped = 0;
+
+            while skipped < self.players.len() && PlayerView::new(self.current_player, &self.state, &self.rules).legal_moves().is_empty() {
+                self.next_player();
+                skipped += 1;
+            }
+
+            self.over = skipped == self.players.len();
+        }
+
+        if self.over {
+            self.events.push(GameEvent::GameOver { result: self.standings() });
+        }
+    }
+
+    /// Informs every player about an observation
+    fn notify(&mut self, observation: Observation) -> Result<()> {
+        for (seat, player) in self.players.iter_mut().enumerate() {
+            player.observe(&observation.redacted_for(seat))?;
+        }
 
         Ok(())
     }
-}
 
-fn rand_card_from_deck(deck: &[usize], rng: &mut impl Rng) -> Result<usize> {
-    let total = deck.iter().sum::<usize>();
-    let rand = rng.gen_range(1..=total);
-    let mut sum = 0;
+    /// Adds cards to a seat's hand, placing and returning the cards of completed sets
+    fn add_to_hand(&mut self, seat: usize, cards: &[Card]) -> Vec<Card> {
+        let placed = self.state.add_to_hand(seat, cards, &self.rules);
 
-    for (i, &count) in deck.iter().enumerate() {
-        sum += count;
-        if rand <= sum {
-            return Ok(i);
+        for cards in placed.chunk_by(|a, b| a.rank == b.rank) {
+            self.events.push(GameEvent::Place { player: seat, cards: cards.to_vec() });
         }
+
+        placed
     }
 
-    Err(anyhow!("Cannot get random card from empty deck"))
+    /// Makes the current player pick up the top card of the deck, returning it or `None` if the deck is empty
+    fn draw(&mut self, outcome: &mut Outcome) -> Result<Option<Card>> {
+        let Some(card) = self.state.draw() else {
+            return Ok(None);
+        };
+
+        self.events.push(GameEvent::Draw { player: self.current_player, card });
+
+        let books = self.add_to_hand(self.current_player, &[card]).len() / self.rules.book_size;
+        outcome.drawn.push(card);
+        outcome.books += books;
+
+        self.notify(Observation::Pickup { player: self.current_player, card: Some(card.rank) })?;
+
+        if books > 0 {
+            self.notify(Observation::Place { player: self.current_player, card: card.rank, books })?;
+        }
+
+        Ok(Some(card))
+    }
 }
 
 impl Bot {
@@ -140,61 +477,99 @@ impl Bot {
         Self {
             network: network.clone(),
             observer,
-            number_placed: 0,
         }
     }
 
-    pub fn eval(&mut self) -> Option<Move> {
-        let mut outputs = self.network.eval(&self.observer_to_inputs()).unwrap().iter().enumerate().map(|(i, &v)| (i, v)).collect::<Vec<_>>();
+    /// The number of network inputs a bot needs in a game of `players` players
+    pub fn inputs(players: usize, rules: &RuleSet) -> usize {
+        players * rules.ranks * INPUTS_PER_UNKNOWN_CARD + rules.ranks + 1
+    }
+
+    /// The number of network outputs a bot needs in a game of `players` players, one for each query
+    pub fn outputs(players: usize, rules: &RuleSet) -> usize {
+        (players - 1) * rules.ranks
+    }
+
+    /// Picks the legal move the network rates highest, or `None` if there is none
+    pub fn eval(&mut self) -> Result<Option<Move>> {
+        let mut outputs = self.network.eval(&self.observer_to_inputs()?)?.iter().enumerate().map(|(i, &v)| (i, v)).collect::<Vec<_>>();
         outputs.sort_by(|a, b| b.1.total_cmp(&a.1));
 
+        // A player with no cards has to pick up
+        if self.observer.move_is_legal(&Move::Pickup) {
+            return Ok(Some(Move::Pickup));
+        }
+
         for &output in outputs.iter() {
-            let node_move = Move::from_id(self.observer.other_players.len(), output.0);
+            let node_move = match Move::from_id(self.observer.other_players.len(), self.observer.rules.ranks, output.0) {
+                Move::Query(query) => Move::Query(Query { player: self.observer.seat_id(query.player), card: query.card }),
+                Move::Pickup => continue,
+            };
+
             if self.observer.move_is_legal(&node_move) {
-                return Some(node_move);
+                return Ok(Some(node_move));
             }
         }
 
-        None
+        Ok(None)
     }
 
-    fn observer_to_inputs(&self) -> Vec<f32> {
-        let mut inputs = Vec::with_capacity((self.observer.other_players.len() + 1) * DECK_SIZE * INPUTS_PER_UNKNOWN_CARD);
+    fn observer_to_inputs(&self) -> Result<Vec<f32>> {
+        let mut inputs = Vec::with_capacity(Self::inputs(self.observer.other_players.len() + 1, &self.observer.rules));
+        // The most cards of a kind anybody can hold without placing them
+        let max = (self.observer.rules.suits - 1).max(1) as f32;
 
         // Add player cards
         for player in self.observer.other_players.iter() {
-            inputs.append(&mut player.cards.cards.iter().flat_map(Self::weights_from_probability).collect());
+            inputs.append(&mut player.cards.cards.iter().flat_map(|probability| Self::weights_from_probability(probability, max)).collect());
         }
 
         // Add deck cards
-        inputs.append(&mut self.observer.deck.cards.iter().flat_map(Self::weights_from_probability).collect());
+        inputs.append(&mut self.observer.deck.cards.iter().flat_map(|probability| Self::weights_from_probability(probability, max)).collect());
 
         // Add own cards
-        if let Some(own_deck) = self.observer.own_deck {
-            inputs.append(&mut own_deck.iter().map(|card| lerp(-1.0..=1., *card as f32 / 3.0)).collect());
-        } else {
-            panic!("Own deck is None");
-        }
+        let Some(own_deck) = &self.observer.own_deck else {
+            return Err(GoFishError::InvalidState("A bot has to know its own hand".to_string()));
+        };
+
+        inputs.append(&mut own_deck.iter().map(|card| lerp(-1.0..=1., *card as f32 / max)).collect());
 
         // Bias
         inputs.push(1.);
 
-        inputs
+        Ok(inputs)
     }
 
-    fn weights_from_probability(probability: &Probability) -> Vec<f32> {
+    fn weights_from_probability(probability: &Probability, max: f32) -> Vec<f32> {
         match probability {
-            Probability::Unknown(x) => vec![-1., lerp(-1.0..=1., x / 3.0)],
-            Probability::Known(x) => vec![0., lerp(-1.0..=1., *x as f32 / 3.0)],
-            Probability::MoreThan(x) => vec![1., lerp(-1.0..=1., *x as f32 / 3.0)],
+            Probability::Unknown(x) => vec![-1., lerp(-1.0..=1., x / max)],
+            Probability::Known(x) => vec![0., lerp(-1.0..=1., *x as f32 / max)],
+            Probability::MoreThan(x) => vec![1., lerp(-1.0..=1., *x as f32 / max)],
         }
     }
 }
 
+impl GoFishPlayer for Bot {
+    fn choose_move(&mut self, _view: &PlayerView) -> Result<Option<Move>> {
+        self.eval()
+    }
+
+    fn observe(&mut self, observation: &Observation) -> Result<()> {
+        self.observer.observe(observation)
+    }
+
+    fn observer(&self) -> Option<&GameObserver> {
+        Some(&self.observer)
+    }
+}
+
 impl Move {
-    pub fn from_id(players: usize, id: usize) -> Self {
-        if id <= players * DECK_SIZE {
-            return Move::Query(Query { player: id / DECK_SIZE, card: id % DECK_SIZE });
+    /// Converts a network output id into a move
+    ///
+    /// The player of a [`Query`] is relative to the observing player, see [`GameObserver::seat_id`].
+    pub fn from_id(players: usize, ranks: usize, id: usize) -> Self {
+        if id < players * ranks {
+            return Move::Query(Query { player: id / ranks, card: id % ranks });
         }
 
         Move::Pickup
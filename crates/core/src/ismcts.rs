@@ -0,0 +1,223 @@
+use rand::seq::SliceRandom;
+
+use crate::{belief::Deals, card::Card, error::{GoFishError, Result}, game::{BotGame, Move}, observer::GameObserver, player::{GoFishPlayer, Observation, PlayerView}, state::GameState, util::{self, GameRng}};
+
+/// A player using information set Monte Carlo tree search
+///
+/// Every iteration deals the hidden cards in a way which agrees with what the [`GameObserver`] knows, plays the
+/// game out with random moves and shares one search tree between all of these deals. The move which was tried most
+/// often is played.
+#[derive(Clone)]
+pub struct IsmctsBot {
+    pub observer: GameObserver,
+    /// The number of simulated games per move
+    pub iterations: usize,
+    /// How much the search prefers trying moves over playing the ones which have done well so far
+    pub exploration: f32,
+    /// The number of moves after which a simulated game is scored as it stands
+    pub max_rollout_moves: usize,
+    rng: GameRng,
+}
+
+/// A move in the search tree, which is shared by every deal the move could be played in
+struct Node {
+    seat: usize,
+    player_move: Move,
+    visits: u32,
+    /// The sum of the rewards of the player who moved
+    reward: f32,
+    /// The number of iterations in which the move could have been chosen
+    available: u32,
+    children: Vec<Node>,
+}
+
+/// Sits in the seats of a simulated game, which is driven by the search instead
+#[derive(Clone)]
+struct Simulated;
+
+impl IsmctsBot {
+    pub fn new(observer: GameObserver, iterations: usize, seed: u64) -> Self {
+        Self {
+            observer,
+            iterations,
+            exploration: 0.7,
+            max_rollout_moves: 1000,
+            rng: util::rng_from_seed(seed),
+        }
+    }
+
+    /// Searches the position and returns how often each legal move was tried
+    ///
+    /// The counts can also be used as a target for training other players.
+    pub fn search(&mut self, view: &PlayerView) -> Result<Vec<(Move, u32)>> {
+        let legal_moves = view.legal_moves();
+        let mut root = Vec::new();
+
+        if legal_moves.len() > 1 {
+            let mut deals = self.observer.deals();
+
+            for _ in 0..self.iterations {
+                let state = determinize(&self.observer, &mut deals, &view.books, &mut self.rng)?;
+                let players = (0..state.hands.len()).map(|_| Box::new(Simulated) as Box<dyn GoFishPlayer>).collect();
+                let mut game = BotGame::from_state(state, view.seat, view.rules, players)?;
+
+                self.iterate(&mut root, &mut game)?;
+            }
+        }
+
+        Ok(legal_moves.into_iter()
+            .map(|m| (m, root.iter().find(|node: &&Node| node.player_move == m).map_or(0, |node| node.visits)))
+            .collect())
+    }
+
+    /// Plays one iteration from a position of the tree, returning the reward of every seat
+    fn iterate(&mut self, children: &mut Vec<Node>, game: &mut BotGame) -> Result<Vec<f32>> {
+        if game.over {
+            return Ok(game.standings().scores());
+        }
+
+        let seat = game.current_player;
+        let legal_moves = PlayerView::new(seat, &game.state, &game.rules).legal_moves();
+
+        for child in children.iter_mut().filter(|child| child.seat == seat && legal_moves.contains(&child.player_move)) {
+            child.available += 1;
+        }
+
+        let untried = legal_moves.iter()
+            .filter(|&&m| !children.iter().any(|child| child.seat == seat && child.player_move == m))
+            .copied()
+            .collect::<Vec<_>>();
+
+        let (index, rewards) = match untried.choose(&mut self.rng) {
+            // Expand the tree by one move and play the rest of the game randomly
+            Some(&m) => {
+                children.push(Node { seat, player_move: m, visits: 0, reward: 0., available: 1, children: Vec::new() });
+                game.apply_move(seat, m)?;
+
+                (children.len() - 1, self.rollout(game)?)
+            },
+            None => {
+                let index = (0..children.len())
+                    .filter(|&i| children[i].seat == seat && legal_moves.contains(&children[i].player_move))
+                    .max_by(|&a, &b| children[a].ucb(self.exploration).total_cmp(&children[b].ucb(self.exploration)))
+                    .ok_or_else(|| GoFishError::InvalidState(format!("Seat {seat} can't move in a simulated game")))?;
+
+                game.apply_move(seat, children[index].player_move)?;
+
+                (index, self.iterate(&mut children[index].children, game)?)
+            },
+        };
+
+        children[index].visits += 1;
+        children[index].reward += rewards[seat];
+
+        Ok(rewards)
+    }
+
+    /// Plays random legal moves until the game is over or `max_rollout_moves` have been played
+    fn rollout(&mut self, game: &mut BotGame) -> Result<Vec<f32>> {
+        for _ in 0..self.max_rollout_moves {
+            if game.over {
+                break;
+            }
+
+            let seat = game.current_player;
+            let Some(&m) = PlayerView::new(seat, &game.state, &game.rules).legal_moves().choose(&mut self.rng) else {
+                break;
+            };
+
+            game.apply_move(seat, m)?;
+        }
+
+        Ok(game.standings().scores())
+    }
+}
+
+impl GoFishPlayer for IsmctsBot {
+    fn choose_move(&mut self, view: &PlayerView) -> Result<Option<Move>> {
+        Ok(self.search(view)?.into_iter().max_by_key(|&(_, visits)| visits).map(|(m, _)| m))
+    }
+
+    fn observe(&mut self, observation: &Observation) -> Result<()> {
+        self.observer.observe(observation)
+    }
+
+    fn observer(&self) -> Option<&GameObserver> {
+        Some(&self.observer)
+    }
+}
+
+impl GoFishPlayer for Simulated {
+    fn choose_move(&mut self, _view: &PlayerView) -> Result<Option<Move>> {
+        Ok(None)
+    }
+}
+
+impl Node {
+    /// The upper confidence bound of the move's reward
+    fn ucb(&self, exploration: f32) -> f32 {
+        let visits = self.visits as f32;
+
+        self.reward / visits + exploration * ((self.available as f32).ln() / visits).sqrt()
+    }
+}
+
+/// Deals the cards the observer can't see in a way which agrees with everything it knows
+///
+/// How many of each rank are where is sampled from the `deals` of the observer, after which the cards are dealt out by
+/// suit and the stock is shuffled. Which seat placed which sets doesn't matter for the rest of the game, so the placed
+/// sets are handed out in order of rank to match `books`.
+fn determinize(observer: &GameObserver, deals: &mut Deals, books: &[usize], rng: &mut GameRng) -> Result<GameState> {
+    let rules = &observer.rules;
+    let players = observer.other_players.len() + 1;
+    let inconsistent = |reason: String| GoFishError::InconsistentObservation(format!("Observer {} can't be dealt: {reason}", observer.id));
+
+    let Some(own_deck) = &observer.own_deck else {
+        return Err(GoFishError::InvalidState("An ISMCTS bot has to know its own hand".to_string()));
+    };
+
+    // The cards of each rank which haven't been dealt yet
+    let mut unseen = (0..rules.ranks)
+        .map(|rank| (0..rules.suits).map(|suit| Card::new(rank, suit)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let mut take = |rank: usize, amount: usize| match unseen[rank].len().checked_sub(amount) {
+        Some(left) => Ok(unseen[rank].split_off(left)),
+        None => Err(inconsistent(format!("there aren't {amount} more cards of rank {rank}"))),
+    };
+
+    let mut placed = vec![Vec::new(); players];
+    let mut seat = 0;
+
+    for rank in 0..rules.ranks {
+        for _ in 0..observer.placed[rank] / rules.book_size {
+            while seat < players && placed[seat].len() >= books[seat] * rules.book_size {
+                seat += 1;
+            }
+
+            if seat == players {
+                return Err(inconsistent("more sets have been placed than the players have".to_string()));
+            }
+
+            placed[seat].extend(take(rank, rules.book_size)?);
+        }
+    }
+
+    let mut deal = |amounts: &[usize]| (0..rules.ranks).map(|rank| take(rank, amounts[rank])).collect::<Result<Vec<_>>>().map(|cards| cards.concat());
+
+    let world = deals.sample(rng)?;
+    let mut hands = vec![Vec::new(); players];
+    hands[observer.id] = deal(own_deck)?;
+
+    for (i, hand) in world.hands.iter().enumerate() {
+        hands[observer.seat_id(i)] = deal(hand)?;
+    }
+
+    let mut stock = deal(&world.stock)?;
+    stock.shuffle(rng);
+
+    let mut state = GameState::new(hands, stock, rules.ranks);
+    state.placed = placed;
+
+    Ok(state)
+}
@@ -1,6 +1,23 @@
+pub mod baseline;
+pub mod belief;
+pub mod card;
+pub mod endgame;
+pub mod error;
 pub mod observer;
 pub mod probability;
 pub mod neural_network;
+pub mod notation;
+pub mod oracle;
 pub mod game;
+pub mod ismcts;
+pub mod event;
+pub mod replay;
+pub mod player;
+pub mod rating;
+pub mod result;
+pub mod rules;
+pub mod state;
+pub mod stats;
+pub mod tournament;
 pub mod util;
 pub mod trainer;
\ No newline at end of file
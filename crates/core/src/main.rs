@@ -1,19 +1,44 @@
-use fish_core::{neural_network::NeuralNetwork, trainer::BotTrainer};
-use rand::thread_rng;
+use fish_core::{game::BotGame, oracle::{self, Agreement}, rules::RuleSet, trainer::BotTrainer, util};
+
+/// The number of generations between measuring how often the fittest network agrees with the oracle
+const AGREEMENT_INTERVAL: u64 = 100;
 
 fn main() {
-    let mut rng = thread_rng();
+    // The seed can be passed as the first argument to reproduce a training run
+    let seed = std::env::args().nth(1).map_or(0, |seed| seed.parse().expect("Seed should be a number"));
+    // The fittest network of every generation is saved here
+    let path = std::env::args().nth(2).unwrap_or_else(|| "bot.json".to_string());
+    println!("Seed: {seed}");
+
     let mut trainer = BotTrainer::new(
         100,
         0.1,
         40,
         4,
-        50,
-        &mut rng
+        1000,
+        RuleSet::default(),
+        seed
     );
 
     for i in 0..10000 {
-        trainer.step(&mut rng).unwrap();
-        println!("Game {i}: Top Fitness: {}", trainer.players.iter().max_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap()).unwrap().fitness);
+        trainer.step(util::derive_seed(seed, i)).unwrap();
+
+        let best = trainer.players.iter().max_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap()).unwrap();
+
+        println!("Game {i}: Top Fitness: {}, Ask success rate: {:.3}", best.fitness, trainer.stats.total().success_rate());
+
+        // How often the best network plays like an oracle in a game against itself, which is slow to find out
+        if i % AGREEMENT_INTERVAL == 0 {
+            let mut game = BotGame::new_rand(&vec![best.network.clone(); trainer.game_size], &trainer.rules, util::derive_seed(seed, i));
+            let mut agreement = Agreement::default();
+
+            for seat in oracle::play_measuring_agreement(&mut game, trainer.max_turns).unwrap() {
+                agreement.merge(&seat);
+            }
+
+            println!("Oracle agreement: {:.3}", agreement.rate());
+        }
+
+        best.network.save(&path).expect("The best network should be saved");
     }
 }
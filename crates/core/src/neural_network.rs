@@ -1,31 +1,52 @@
-use anyhow::{Result, Context};
+use std::{fs, path::Path};
+
 use rand::Rng;
 use rand_distr::StandardNormal;
+use serde::{Serialize, Deserialize};
+
+use crate::{error::{GoFishError, Result}, util};
 
 const MUTATION_RATE: f32 = 0.1;
 
-type Activation = fn(&mut [Neuron]);
+/// The activation function applied to a whole layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Activation {
+    Tanh,
+    Softmax,
+}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NeuralNetwork {
     layers: Vec<Layer>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Layer {
     neurons: Vec<Neuron>,
     activation: Activation,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Neuron {
     weights: Vec<f32>,
     bias: f32,
+    #[serde(skip)]
     pub value: f32,
 }
 
 impl NeuralNetwork {
+    /// Evaluates the network, failing if it has no layers or the amount of inputs doesn't match the network
     pub fn eval(&mut self, inputs: &[f32]) -> Result<Vec<f32>> {
+        if self.layers.is_empty() {
+            return Err(GoFishError::EmptyNetwork);
+        }
+
+        let expected = self.inputs();
+
+        if inputs.len() != expected {
+            return Err(GoFishError::ShapeMismatch { expected, actual: inputs.len() });
+        }
+
         for i in 0..self.layers.len() {
             let inputs = if i > 0 {
                 self.layers[i - 1].neurons.iter().map(|neuron| neuron.value).collect::<Vec<f32>>()
@@ -36,10 +57,24 @@ impl NeuralNetwork {
             self.layers[i].eval(inputs.as_slice());
         }
 
-        Ok(self.layers.last().context("Invalid network size")?.neurons.iter().map(|neuron| neuron.value).collect())
+        Ok(self.layers.last().map_or_else(Vec::new, |layer| layer.neurons.iter().map(|neuron| neuron.value).collect()))
+    }
+
+    /// The amount of inputs the network takes
+    pub fn inputs(&self) -> usize {
+        self.layers.first()
+            .and_then(|layer| layer.neurons.first())
+            .map_or(0, |neuron| neuron.weights.len())
     }
 
+    /// Builds a network with random weights and biases and a layer for every size and activation in `architecture`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `architecture` is empty, as a network needs at least one layer.
     pub fn new_rand(inputs: usize, architecture: &[(usize, Activation)], rng: &mut impl Rng) -> Self {
+        assert!(!architecture.is_empty(), "A network needs at least one layer");
+
         Self {
             layers: architecture.iter()
                 .enumerate()
@@ -77,6 +112,24 @@ impl NeuralNetwork {
         }
     }
 
+    /// Saves the network as JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, serde_json::to_string(self)?)?;
+
+        Ok(())
+    }
+
+    /// Loads a network saved with [`NeuralNetwork::save`], failing if it has no layers
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let network: Self = serde_json::from_str(&fs::read_to_string(path)?)?;
+
+        if network.layers.is_empty() {
+            return Err(GoFishError::EmptyNetwork);
+        }
+
+        Ok(network)
+    }
+
     pub fn mutate(&mut self, rng: &mut impl Rng) {
         for layer in self.layers.iter_mut() {
             for neuron in layer.neurons.iter_mut() {
@@ -93,10 +146,10 @@ impl NeuralNetwork {
 impl Layer {
     pub fn eval(&mut self, inputs: &[f32]) {
         for neuron in self.neurons.iter_mut() {
-            neuron.eval(inputs, self.activation);
+            neuron.eval(inputs);
         }
 
-        (self.activation)(&mut self.neurons);
+        self.activation.apply(&mut self.neurons);
     }
 
     pub fn new_rand(size: usize, prev_size: usize, rng: &mut impl Rng, activation: Activation) -> Self {
@@ -107,8 +160,17 @@ impl Layer {
     }
 }
 
+impl Activation {
+    fn apply(&self, neurons: &mut [Neuron]) {
+        match self {
+            Activation::Tanh => util::ac_tanh(neurons),
+            Activation::Softmax => util::ac_softmax(neurons),
+        }
+    }
+}
+
 impl Neuron {
-    pub fn eval(&mut self, inputs: &[f32], activation: Activation) {
+    pub fn eval(&mut self, inputs: &[f32]) {
         self.value = inputs
             .iter()
             .enumerate()
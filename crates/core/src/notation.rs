@@ -0,0 +1,323 @@
+use std::fmt::Write;
+
+use crate::{card::Card, error::{GoFishError, Result}, event::GameEvent, game::{BotGame, Move, Query}, observer::GameObserver, player::{GoFishPlayer, Observation, PlayerView}, replay::Replay, rules::RuleSet};
+
+/// Writes a game as one line per deal and move
+///
+/// ```text
+/// Rules: {"ranks":6, ...}
+/// P0 dealt: 0C 3D 3C 5H 5S 1C 2D
+/// P1 dealt: 1D 2H 4S 0H 3S 3H 5D
+/// P0 asks P1 for 3: got 2, BOOK
+/// P0 asks P1 for 5: got 1
+/// P0 asks P1 for 1: got 1
+/// P0 asks P1 for 5: go fish, drew 5C, BOOK
+/// ```
+///
+/// Players are referred to by their seat and cards are written as described by [`Card`]'s `Display`
+/// implementation, while asks only name the rank. Picking up with an empty hand is written as `P1 draws 4H`, with the
+/// cards of several pickups in a row on the same line, and the end of the game as `Game over: 3 1` with the books of
+/// each seat. The rules are left out if they are the
+/// default ones. Which cards were given and placed isn't written down, as it follows from the deal.
+pub fn write(replay: &Replay) -> String {
+    let mut lines = Vec::new();
+
+    if replay.rules != RuleSet::default() {
+        lines.push(Line::Rules(replay.rules.clone()).to_string());
+    }
+
+    let mut pending: Option<Line> = None;
+
+    for event in replay.events.iter() {
+        match *event {
+            GameEvent::Deal { seat, ref hand } => {
+                lines.extend(pending.take().map(|line| line.to_string()));
+                lines.push(Line::Deal { seat, hand: hand.clone() }.to_string());
+            },
+            GameEvent::Ask { player, target, card } => {
+                lines.extend(pending.take().map(|line| line.to_string()));
+                pending = Some(Line::Ask { player, target, card, got: None, drew: None, books: 0 });
+            },
+            GameEvent::Give { ref cards, .. } => {
+                if let Some(Line::Ask { got, .. }) = &mut pending {
+                    *got = Some(cards.len());
+                }
+            },
+            GameEvent::Draw { player, card } => match &mut pending {
+                // Only the first draw after a failed ask is part of the ask
+                Some(Line::Ask { got: None, drew: drew @ None, .. }) => *drew = Some(card),
+                Some(Line::Pickup { player: drawing, cards, .. }) if *drawing == player => cards.push(card),
+                _ => {
+                    lines.extend(pending.take().map(|line| line.to_string()));
+                    pending = Some(Line::Pickup { player, cards: vec![card], books: 0 });
+                },
+            },
+            GameEvent::Place { ref cards, .. } => {
+                // Sets placed straight from the deal aren't part of a move
+                if let Some(Line::Ask { books, .. } | Line::Pickup { books, .. }) = &mut pending {
+                    *books += cards.len() / replay.rules.book_size;
+                }
+            },
+            GameEvent::GameOver { ref result } => {
+                lines.extend(pending.take().map(|line| line.to_string()));
+                lines.push(Line::GameOver(result.books.clone()).to_string());
+            },
+        }
+    }
+
+    lines.extend(pending.take().map(|line| line.to_string()));
+
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+/// Reads a game written with [`write`] into a [`Replay`]
+///
+/// Every move is played out, so a game which breaks the rules or doesn't add up is an error.
+pub fn parse(text: &str) -> Result<Replay> {
+    Ok(parse_game(text, |observer| Box::new(Recorded(observer)))?.replay())
+}
+
+/// Plays a game written with [`write`], seating the player `seat` builds from each seat's [`GameObserver`]
+///
+/// The players only observe the recorded moves, so the game can be continued with [`BotGame::step`] if the
+/// record stops before the end. The cards which are never drawn in the record are left in the deck in order.
+pub fn parse_game(text: &str, seat: impl FnMut(GameObserver) -> Box<dyn GoFishPlayer>) -> Result<BotGame> {
+    let mut rules = RuleSet::default();
+    let mut hands = Vec::new();
+    let mut moves = Vec::new();
+
+    for (i, text) in text.lines().enumerate() {
+        let line = i + 1;
+
+        match parse_line(text).map_err(|reason| GoFishError::Notation { line, reason })? {
+            None => {},
+            Some(Line::Rules(parsed)) => rules = parsed,
+            Some(Line::Deal { seat, hand }) if seat == hands.len() => hands.push(hand),
+            Some(Line::Deal { seat, .. }) => return Err(GoFishError::Notation { line, reason: format!("Expected the deal of P{} instead of P{seat}", hands.len()) }),
+            Some(parsed) => moves.push((line, parsed)),
+        }
+    }
+
+    // Stack the deck so the recorded cards are drawn in order
+    let mut stock = Card::full_deck(&rules);
+    let mut drawn = Vec::new();
+
+    let dealt = hands.iter().flatten().map(|&card| (0, card));
+    let recorded = moves.iter().flat_map(|(line, parsed)| match parsed {
+        Line::Ask { drew, .. } => drew.iter().map(|&card| (*line, card)).collect(),
+        Line::Pickup { cards, .. } => cards.iter().map(|&card| (*line, card)).collect(),
+        _ => Vec::new(),
+    });
+
+    for (line, card) in dealt.chain(recorded) {
+        let Some(position) = stock.iter().position(|&other| other == card) else {
+            return Err(GoFishError::Notation { line, reason: format!("{card} isn't in the deck or appears twice") });
+        };
+
+        stock.remove(position);
+
+        if line > 0 {
+            drawn.push(card);
+        }
+    }
+
+    stock.extend(drawn.into_iter().rev());
+
+    let mut game = BotGame::from_deal(hands, stock, &rules, seat)?;
+
+    for (line, parsed) in moves {
+        let mismatch = |reason: String| GoFishError::Notation { line, reason };
+
+        match parsed {
+            Line::Ask { player, target, card, got, drew, books } => {
+                let outcome = game.apply_move(player, Move::Query(Query { player: target, card })).map_err(|err| mismatch(err.to_string()))?;
+
+                if outcome.received.len() != got.unwrap_or(0) || outcome.drawn.first() != drew.as_ref() || outcome.books != books {
+                    return Err(mismatch(format!("The ask actually went {}", Line::Ask {
+                        player, target, card,
+                        got: (!outcome.received.is_empty()).then_some(outcome.received.len()),
+                        drew: outcome.drawn.first().copied(),
+                        books: outcome.books,
+                    })));
+                }
+            },
+            Line::Pickup { player, cards, books } => {
+                // A player who empties their hand again picks up once more, which is written on the same line
+                let (mut drawn, mut placed) = (Vec::new(), 0);
+
+                loop {
+                    let outcome = game.apply_move(player, Move::Pickup).map_err(|err| mismatch(err.to_string()))?;
+                    drawn.extend(outcome.drawn);
+                    placed += outcome.books;
+
+                    if drawn.len() >= cards.len() || !cards.starts_with(&drawn) {
+                        break;
+                    }
+                }
+
+                if drawn != cards || placed != books {
+                    return Err(mismatch(format!("The pickup actually went {}", Line::Pickup { player, cards: drawn, books: placed })));
+                }
+            },
+            Line::GameOver(books) => {
+                if !game.over || game.standings().books != books {
+                    return Err(mismatch(format!("The game isn't over with {books:?} books")));
+                }
+            },
+            Line::Rules(_) | Line::Deal { .. } => unreachable!("Rules and deals aren't moves"),
+        }
+    }
+
+    Ok(game)
+}
+
+/// A single line of notation
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Line {
+    Rules(RuleSet),
+    Deal { seat: usize, hand: Vec<Card> },
+    /// `got` is `None` if the player had to go fish
+    Ask { player: usize, target: usize, card: usize, got: Option<usize>, drew: Option<Card>, books: usize },
+    Pickup { player: usize, cards: Vec<Card>, books: usize },
+    GameOver(Vec<usize>),
+}
+
+impl std::fmt::Display for Line {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Line::Rules(rules) => write!(f, "Rules: {}", serde_json::to_string(rules).map_err(|_| std::fmt::Error)?),
+            Line::Deal { seat, hand } => write!(f, "P{seat} dealt: {}", cards_to_string(hand)),
+            Line::Ask { player, target, card, got, drew, books } => {
+                write!(f, "P{player} asks P{target} for {card}: ")?;
+
+                match (got, drew) {
+                    (Some(got), _) => write!(f, "got {got}")?,
+                    (None, Some(drew)) => write!(f, "go fish, drew {drew}")?,
+                    (None, None) => write!(f, "go fish")?,
+                }
+
+                write!(f, "{}", books_to_string(*books))
+            },
+            Line::Pickup { player, cards, books } => write!(f, "P{player} draws {}{}", cards_to_string(cards), books_to_string(*books)),
+            Line::GameOver(books) => write!(f, "Game over: {}", books.iter().map(|books| books.to_string()).collect::<Vec<_>>().join(" ")),
+        }
+    }
+}
+
+/// Parses a line, returning `None` for blank lines and `#` comments
+fn parse_line(line: &str) -> std::result::Result<Option<Line>, String> {
+    let line = line.trim();
+
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    if let Some(rules) = line.strip_prefix("Rules:") {
+        return serde_json::from_str(rules.trim()).map(|rules| Some(Line::Rules(rules))).map_err(|err| err.to_string());
+    }
+
+    if let Some(books) = line.strip_prefix("Game over:") {
+        return books.split_whitespace()
+            .map(|books| books.parse().map_err(|_| format!("Invalid amount of books {books:?}")))
+            .collect::<std::result::Result<_, _>>()
+            .map(|books| Some(Line::GameOver(books)));
+    }
+
+    let (player, rest) = line.split_once(' ').ok_or_else(|| format!("Can't read {line:?}"))?;
+    let player = parse_seat(player)?;
+
+    if let Some(hand) = rest.strip_prefix("dealt:") {
+        return Ok(Some(Line::Deal { seat: player, hand: parse_cards(hand)? }));
+    }
+
+    if let Some(rest) = rest.strip_prefix("asks ") {
+        let (target, rest) = rest.split_once(" for ").ok_or("Expected \"for\" after the asked player")?;
+        let (card, outcome) = rest.split_once(':').ok_or("Expected \":\" after the asked rank")?;
+        let card = card.trim().parse().map_err(|_| format!("Invalid rank {card:?}"))?;
+
+        let mut parts = outcome.split(',').map(str::trim).collect::<Vec<_>>();
+        let books = parse_books(&mut parts)?;
+
+        let (got, drew) = match parts.as_slice() {
+            ["go fish"] => (None, None),
+            ["go fish", drew] => (None, Some(drew.strip_prefix("drew ").ok_or("Expected \"drew\" after \"go fish\"")?.parse()?)),
+            [got] => match got.strip_prefix("got ").map(str::parse) {
+                Some(Ok(got)) if got > 0 => (Some(got), None),
+                _ => return Err(format!("Invalid outcome {got:?}")),
+            },
+            _ => return Err(format!("Invalid outcome {outcome:?}")),
+        };
+
+        return Ok(Some(Line::Ask { player, target: parse_seat(target)?, card, got, drew, books }));
+    }
+
+    if let Some(rest) = rest.strip_prefix("draws ") {
+        let mut parts = rest.split(',').map(str::trim).collect::<Vec<_>>();
+        let books = parse_books(&mut parts)?;
+
+        return match parts.as_slice() {
+            [cards] => Ok(Some(Line::Pickup { player, cards: parse_cards(cards)?, books })),
+            _ => Err(format!("Invalid pickup {rest:?}")),
+        };
+    }
+
+    Err(format!("Can't read {line:?}"))
+}
+
+fn parse_seat(seat: &str) -> std::result::Result<usize, String> {
+    seat.trim().strip_prefix('P').and_then(|seat| seat.parse().ok()).ok_or_else(|| format!("Invalid player {seat:?}"))
+}
+
+fn parse_cards(cards: &str) -> std::result::Result<Vec<Card>, String> {
+    cards.split_whitespace().map(str::parse).collect()
+}
+
+/// Removes a trailing `BOOK` or `n BOOKS` from the parts of a move, returning the amount of sets placed
+fn parse_books(parts: &mut Vec<&str>) -> std::result::Result<usize, String> {
+    let books = match parts.last().copied() {
+        Some("BOOK") => 1,
+        Some(last) if last.ends_with(" BOOKS") => last.trim_end_matches(" BOOKS").parse().map_err(|_| format!("Invalid amount of books {last:?}"))?,
+        _ => return Ok(0),
+    };
+
+    parts.pop();
+
+    Ok(books)
+}
+
+fn cards_to_string(cards: &[Card]) -> String {
+    cards.iter().fold(String::new(), |mut text, card| {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+
+        write!(text, "{card}").expect("Writing to a string can't fail");
+        text
+    })
+}
+
+fn books_to_string(books: usize) -> String {
+    match books {
+        0 => String::new(),
+        1 => ", BOOK".to_string(),
+        books => format!(", {books} BOOKS"),
+    }
+}
+
+/// Follows a recorded game without ever choosing a move
+#[derive(Clone)]
+struct Recorded(GameObserver);
+
+impl GoFishPlayer for Recorded {
+    fn choose_move(&mut self, _view: &PlayerView) -> Result<Option<Move>> {
+        Ok(None)
+    }
+
+    fn observe(&mut self, observation: &Observation) -> Result<()> {
+        self.0.observe(observation)
+    }
+
+    fn observer(&self) -> Option<&GameObserver> {
+        Some(&self.0)
+    }
+}
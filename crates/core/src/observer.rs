@@ -1,19 +1,22 @@
+use rand::Rng;
 use serde::Serialize;
-use anyhow::{Result, anyhow};
-use crate::{probability::Probability, game::Move};
+use crate::{belief::{Beliefs, Deals}, error::{GoFishError, Result}, probability::Probability, game::Move, player::Observation, rules::{RuleSet, EmptyHandRule}};
 
-/// The number of different cards
-pub const DECK_SIZE: usize = 13; // We only need to know the value of the card, not the suit.
-/// The number of card suits
-pub const SUITS: usize = 4;
-/// The number of cards a player should start with
-pub const STARTING_CARDS: usize = 7;
-
-/// Represents a deck where we know what each card is unlike [`Deck`]
-pub type KnownDeck = [usize; DECK_SIZE];
+/// Represents a deck where we know what each card is unlike [`Deck`], with one count per rank
+pub type KnownDeck = Vec<usize>;
 
+/// A deal of the cards an observer can't see
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct World {
+    /// The cards of each other player, in the order of [`GameObserver::other_players`]
+    pub hands: Vec<KnownDeck>,
+    pub stock: KnownDeck,
+}
 
 /// Used for estimating what other players could have based on the observing player's observations
+///
+/// What the observer believes is kept in `beliefs`, from which the estimates in `other_players` and `deck` are
+/// updated after every observation.
 #[derive(Debug, Clone, Serialize)]
 pub struct GameObserver {
     pub deck: Deck,
@@ -21,189 +24,318 @@ pub struct GameObserver {
     pub other_players: Vec<Player>,
     #[serde(rename = "ownDeck")]
     pub own_deck: Option<KnownDeck>,
+    /// The amount of each card which has been placed in sets
+    pub placed: KnownDeck,
     pub id: usize,
+    pub rules: RuleSet,
+    #[serde(skip)]
+    pub beliefs: Beliefs,
+    /// The beliefs from before assuming that the last pickup didn't complete a set, in case its placement follows
+    #[serde(skip)]
+    unplaced: Option<Beliefs>,
 }
 
 impl GameObserver {
     /// Creates a new game observer
     /// 
-    /// Set `own_deck` to `None` if you are not participating in the game as a player.
-    pub fn new(other_players: usize, own_deck: Option<KnownDeck>, id: usize) -> Self {
-        let mut deck = Deck::new_full();
-
-        if let Some(own_deck) = own_deck {
-            deck.remove(&Deck::from_known(own_deck));
+    /// Set `own_deck` to `None` if you are not participating in the game as a player. Any sets in `own_deck` are
+    /// placed straight away, while sets dealt to other players have to be observed with [`GameObserver::place`].
+    pub fn new(other_players: usize, own_deck: Option<KnownDeck>, id: usize, rules: &RuleSet) -> Self {
+        let own = own_deck.clone().unwrap_or_else(|| vec![0; rules.ranks]);
+        let starting_cards = rules.starting_cards(other_players + 1);
+
+        let mut sizes = vec![starting_cards; other_players];
+        sizes.push(rules.deck_size() - own.iter().sum::<usize>() - starting_cards * other_players);
+
+        let mut observer = Self {
+            deck: Deck::new_empty(rules.ranks),
+            other_players: (0..other_players).map(|_| Player { cards: Deck::new_empty(rules.ranks) }).collect(),
+            beliefs: Beliefs::new(&own.iter().map(|&held| rules.suits - held).collect::<Vec<_>>(), sizes),
+            unplaced: None,
+            own_deck,
+            placed: vec![0; rules.ranks],
+            id,
+            rules: rules.clone(),
+        };
+
+        // Place any sets we were dealt
+        for card in 0..rules.ranks {
+            observer.self_add(card, 0);
         }
 
-        Self {
-            // cards_remaining: DECK_SIZE - (7 * players),
-            other_players: (0..other_players).map(|_| Player::with_starting_cards(&mut deck, STARTING_CARDS)).collect(),
-            deck,
-            own_deck,
-            id
+        observer.update_estimates();
+
+        observer
+    }
+
+    /// Observes something which happened in the game, converting seats into ids
+    pub fn observe(&mut self, observation: &Observation) -> Result<()> {
+        match *observation {
+            Observation::Pickup { player, card } if player == self.id => {
+                if let Some(card) = card {
+                    self.self_pickup(card)?;
+                }
+            },
+            Observation::Pickup { player, .. } => self.pickup(self.other_id(player))?,
+            Observation::Query { player, target, card, amount, .. } if player == self.id => self.self_query(self.other_id(target), card, amount)?,
+            Observation::Query { player, target, card, amount: 0, .. } if target == self.id => self.self_refuse(self.other_id(player), card)?,
+            Observation::Query { player, target, card, books, .. } if target == self.id => self.self_give_all(self.other_id(player), card, books)?,
+            Observation::Query { player, target, card, amount, books } => self.query(self.other_id(player), self.other_id(target), card, amount, books)?,
+            Observation::Place { player, .. } if player == self.id => {},
+            Observation::Place { player, card, books } => self.place(self.other_id(player), card, books)?,
         }
+
+        Ok(())
     }
 
     /// Observes a player picking up a card from the deck
-    pub fn pickup(&mut self, player: usize) {
-        self.other_players[player].cards.add_unknown_from_other(&mut self.deck, 1);
+    ///
+    /// Since a completed set is placed right away, the player is assumed not to have completed one until its
+    /// placement is observed with [`GameObserver::place`].
+    pub fn pickup(&mut self, player: usize) -> Result<()> {
+        self.beliefs.draw(player)?;
+
+        // Unless the card has to have completed a set, in which case its placement is observed next
+        let book_size = self.rules.book_size;
+        let tracked = (0..self.rules.ranks).filter(|&card| self.beliefs.is_tracked(card, player)).collect::<Vec<_>>();
+        let mut no_set = self.beliefs.clone();
+
+        let fits = tracked.into_iter()
+            .try_for_each(|card| no_set.condition(card, player, |held| held < book_size))
+            .and_then(|_| no_set.settle());
+
+        self.unplaced = fits.is_ok().then(|| std::mem::replace(&mut self.beliefs, no_set));
+
+        self.refresh()
     }
 
     /// Observes a player asking another player for a card
     ///
     /// # Arguments
     /// 
+    /// * `current_player` - The id of the player who asked
     /// * `player` - The id of the player which the current player asked
     /// * `card` - The id of the card which the player asked for
-    /// * `amount_received` - The amount of cards the player received
-    /// * `placed` - Whether the player placed a set
-    pub fn query(&mut self, current_player: usize, player: usize, card: usize, amount_received: usize, placed: bool) -> Result<()> {
-        self.other_players[player].cards.cards[card] = Probability::Known(0);
-
-        if placed {
-            self.remove_all_cards_with_id(card);
-            self.deck.size -= SUITS;
-            return Ok(());
+    /// * `amount_received` - The amount of cards the player received, which is 0 if they had to go fish
+    /// * `books` - The amount of sets the player placed afterwards
+    ///
+    /// Even a failed ask shows that the player who asked holds the card, which they keep holding until they are
+    /// seen to give it away or place it.
+    pub fn query(&mut self, current_player: usize, player: usize, card: usize, amount_received: usize, books: usize) -> Result<()> {
+        self.unplaced = None;
+
+        self.asked(current_player, card)?;
+        self.beliefs.condition(card, player, |held| held == amount_received)?;
+        self.beliefs.change(card, player, |_| 0)?;
+        self.beliefs.change(card, current_player, |held| held + amount_received)?;
+        self.beliefs.sizes[player] -= amount_received;
+        self.beliefs.sizes[current_player] += amount_received;
+
+        self.place_sets(current_player, card, books)?;
+        self.refresh()
+    }
+
+    /// Observes a player placing sets of a card
+    pub fn place(&mut self, player: usize, card: usize, books: usize) -> Result<()> {
+        if let Some(unplaced) = self.unplaced.take() {
+            self.beliefs = unplaced;
         }
 
-        self.other_players[current_player].cards.size += amount_received;
+        self.place_sets(player, card, books)?;
+        self.refresh()
+    }
 
-        match amount_received {
-            2 => self.other_players[current_player].cards.cards[card] = Probability::Known(3),
-            1 => self.other_players[current_player].cards.cards[card] = Probability::MoreThan(2),
-            _ => return Err(anyhow!("Invalid amount of cards received"))
+    /// Observes a player picking up a card from the deck
+    pub fn self_pickup(&mut self, card: usize) -> Result<()> {
+        if self.own_deck.is_some() {
+            self.unplaced = None;
+            self.beliefs.reveal_draw(card)?;
+            self.self_add(card, 1);
+            self.refresh()?;
         }
 
         Ok(())
     }
 
-    /// Observes a player placing a set
-    pub fn place(&mut self, card: usize) {
-        self.remove_all_cards_with_id(card);
-        self.deck.remove_known_cards(card, SUITS);
+    /// Observes the observing player asking another player for a card
+    pub fn self_query(&mut self, player: usize, card: usize, amount_received: usize) -> Result<()> {
+        if self.own_deck.is_some() {
+            self.unplaced = None;
+            self.beliefs.condition(card, player, |held| held == amount_received)?;
+            self.beliefs.change(card, player, |_| 0)?;
+            self.beliefs.sizes[player] -= amount_received;
+            self.self_add(card, amount_received);
+            self.refresh()?;
+        }
+
+        Ok(())
     }
 
-    /// Observes a player picking up a card from the deck
-    pub fn self_pickup(&mut self, card: usize) {
+    /// Observes the observing player giving a set to another player
+    ///
+    /// `books` is the amount of sets the other player placed afterwards.
+    pub fn self_give_all(&mut self, player: usize, card: usize, books: usize) -> Result<()> {
         if let Some(own_deck) = &mut self.own_deck {
-            self.deck.remove_known_cards(card, 1);
-            own_deck[card] += 1;
+            let amount = own_deck[card];
+            own_deck[card] = 0;
 
-            if own_deck[card] == 4 {
-                own_deck[card] = 0;
+            if amount == 0 {
+                return Err(GoFishError::InconsistentObservation(format!("Seat {} asked for {card}, which the observer doesn't hold", self.seat_id(player))));
             }
-        }
-    }
 
-    /// Observes the observing player asking another player for a card
-    pub fn self_query(&mut self, player: usize, card: usize, amount_received: usize) {
-        if let Some(own_deck) = &mut self.own_deck {
-            own_deck[card] += amount_received;
-            self.other_players[player].cards.remove_known_cards(card, amount_received);
+            self.unplaced = None;
+            self.asked(player, card)?;
+            self.beliefs.change(card, player, |held| held + amount)?;
+            self.beliefs.sizes[player] += amount;
 
-            if own_deck[card] == 4 {
-                own_deck[card] = 0;
-            }
+            self.place_sets(player, card, books)?;
+            self.refresh()?;
         }
+
+        Ok(())
     }
 
-    /// Observes the observing player giving a set to another player
-    pub fn self_give_all(&mut self, player: usize, card: usize) -> Result<()> {
-        if let Some(own_deck) = &mut self.own_deck {
-            match own_deck[card] {
-                1 => self.other_players[player].cards.cards[card] = Probability::MoreThan(2),
-                2 => self.other_players[player].cards.cards[card] = Probability::Known(3),
-                3 => self.other_players[player].cards.cards[card] = Probability::Known(0),
-                _ => return Err(anyhow!("Invalid amount of cards to give"))
+    /// Observes another player asking the observing player for a card it doesn't hold
+    pub fn self_refuse(&mut self, player: usize, card: usize) -> Result<()> {
+        if let Some(own_deck) = &self.own_deck {
+            if own_deck[card] > 0 {
+                return Err(GoFishError::InconsistentObservation(format!("Seat {} got nothing when asking for {card}, which the observer holds", self.seat_id(player))));
             }
 
-            own_deck[card] = 0;
+            self.unplaced = None;
+            self.asked(player, card)?;
+            self.refresh()?;
         }
 
         Ok(())
     }
 
+    /// The deals of the cards the observer can't see which agree with everything it has observed, weighted
+    /// according to [`GameObserver::beliefs`] as described in [`Beliefs::deals`]
+    ///
+    /// Reuse the result to sample many deals of the same position.
+    pub fn deals(&self) -> Deals {
+        self.beliefs.deals(self.rules.book_size)
+    }
+
+    /// Deals the cards the observer can't see at random in a way which agrees with everything it has observed
+    pub fn sample_world(&self, rng: &mut impl Rng) -> Result<World> {
+        self.deals().sample(rng)
+    }
+
+    /// Every deal of the cards the observer can't see which agrees with what it has observed with its probability,
+    /// or `None` if there are more than `max_worlds`
+    ///
+    /// This is only feasible with few unseen cards, like on small decks or once the deck is empty.
+    pub fn worlds(&self, max_worlds: usize) -> Result<Option<Vec<(World, f64)>>> {
+        self.deals().worlds(max_worlds)
+    }
+
+    /// Checks whether the observing player is allowed to make a move
+    ///
+    /// Picking up is only allowed when the observing player has run out of cards.
     pub fn move_is_legal(&self, m: &Move) -> bool {
         match m {
-            Move::Pickup => self.deck.total() > 0.,
-            Move::Query(q) => q.player != self.id && self.own_deck.is_some_and(|own_deck| own_deck[q.card] > 0),
+            Move::Pickup => self.rules.empty_hand != EmptyHandRule::SitOut
+                && self.deck.size > 0
+                && self.own_deck.as_ref().is_some_and(|own_deck| own_deck.iter().all(|&card| card == 0)),
+            Move::Query(q) => q.player != self.id
+                && q.player <= self.other_players.len()
+                && self.own_deck.as_ref().is_some_and(|own_deck| own_deck.get(q.card).is_some_and(|&amount| amount > 0)),
         }
     }
 
-    fn remove_all_cards_with_id(&mut self, id: usize) {
-        for player in self.other_players.iter_mut() {
-            player.cards.cards[id] = Probability::Known(0);
+    /// Converts the seat of a player in the game into their index in `other_players`
+    pub fn other_id(&self, seat: usize) -> usize {
+        seat - (seat > self.id) as usize
+    }
+
+    /// Converts an index in `other_players` into the seat of that player in the game
+    pub fn seat_id(&self, other: usize) -> usize {
+        other + (other >= self.id) as usize
+    }
+
+    /// Adds cards to the observing player's hand and places any complete sets
+    fn self_add(&mut self, card: usize, amount: usize) {
+        if let Some(own_deck) = &mut self.own_deck {
+            own_deck[card] += amount;
+
+            let placed = own_deck[card] - own_deck[card] % self.rules.book_size;
+            own_deck[card] -= placed;
+            self.placed[card] += placed;
         }
+    }
 
-        self.deck.cards[id] = Probability::Known(0);
+    /// Updates what we know about a player who asked for a card
+    fn asked(&mut self, player: usize, card: usize) -> Result<()> {
+        // A player can only ask for a card they have, and would have placed it if they had a set
+        let book_size = self.rules.book_size;
+
+        self.beliefs.condition(card, player, |held| (1..book_size).contains(&held))
     }
-}
 
-#[derive(Debug, Clone, Serialize)]
-pub struct Player {
-    pub cards: Deck,
-}
+    /// Updates what we know about a player who placed all complete sets of a card they hold
+    fn place_sets(&mut self, player: usize, card: usize, books: usize) -> Result<()> {
+        let book_size = self.rules.book_size;
+        let amount = books * book_size;
 
-impl Player {
-    pub fn with_starting_cards(deck: &mut Deck, amount: usize) -> Self {
-        let mut cards = Deck::new_empty();
-        cards.add_unknown_from_other(deck, amount);
+        self.beliefs.condition(card, player, |held| held / book_size == books)?;
+        self.beliefs.change(card, player, |held| held - amount)?;
+        self.beliefs.sizes[player] -= amount;
+        self.placed[card] += amount;
 
-        Self {
-            cards,
-        }
+        Ok(())
     }
-}
 
-impl Default for Player {
-    fn default() -> Self {
-        Self {
-            cards: Deck::new_empty()
+    /// Rules out what doesn't fit into the hands and updates the estimates
+    fn refresh(&mut self) -> Result<()> {
+        self.beliefs.settle()?;
+        self.update_estimates();
+
+        Ok(())
+    }
+
+    fn update_estimates(&mut self) {
+        let mut estimates = self.beliefs.estimates();
+        let stock = self.beliefs.stock();
+
+        self.deck.cards = estimates.pop().expect("The stock always has an estimate");
+        self.deck.size = self.beliefs.sizes[stock];
+
+        for (i, (player, cards)) in self.other_players.iter_mut().zip(estimates).enumerate() {
+            player.cards.cards = cards;
+            player.cards.size = self.beliefs.sizes[i];
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct Player {
+    pub cards: Deck,
+}
+
 /// Represents a collection of cards based on their probability
 #[derive(Debug, Clone, Serialize)]
 pub struct Deck {
-    pub cards: [Probability; DECK_SIZE],
+    pub cards: Vec<Probability>,
     pub size: usize,
 }
 
 impl Deck {
-    /// Constructs a new empty `Deck`
-    pub fn new_empty() -> Self {
+    /// Constructs a new empty `Deck` with room for `ranks` different cards
+    pub fn new_empty(ranks: usize) -> Self {
         Self {
-            cards: [Probability::Unknown(0.0); DECK_SIZE],
+            cards: vec![Probability::Unknown(0.0); ranks],
             size: 0,
         }
     }
 
-    /// Constructs a new full `Deck`
-    pub fn new_full() -> Self {
+    /// Constructs a new full `Deck` for the deck described by `rules`
+    pub fn new_full(rules: &RuleSet) -> Self {
         Self {
-            cards: [Probability::Unknown(SUITS as f32); DECK_SIZE],
-            size: SUITS * DECK_SIZE,
-        }
-    }
-
-    /// Removes all cards from an other deck from this `Deck`
-    pub fn remove(&mut self, other: &Self) {
-        for (i, card) in self.cards.iter_mut().enumerate() {
-            *card -= other.cards[i];
+            cards: vec![Probability::Unknown(rules.suits as f32); rules.ranks],
+            size: rules.deck_size(),
         }
-
-        self.size -= other.size;
-    }
-
-    /// Removes an amount of a certain card from this `Deck`
-    pub fn remove_known_cards(&mut self, card: usize, amount: usize) {
-        self.cards[card] -= Probability::Known(amount);
-        self.size -= amount;
-    }
-
-    fn nonzero_len(&self) -> usize {
-        self.cards.iter().filter(|&x| x.value() > 0.0).count()
     }
 
     /// Returns the total value of all card probabilities
@@ -211,27 +343,10 @@ impl Deck {
         self.cards.iter().map(|x| x.value()).sum()
     }
 
-    /// Adds an unknown amount of a certain card to this `Deck` from an other `Deck`
-    pub fn add_unknown_from_other(&mut self, other: &mut Self, amount: usize) {
-        let mut tmp = [0.0; DECK_SIZE];
-
-        for (i, card) in self.cards.iter_mut().enumerate() {
-            let value = (other.cards[i].value() / other.total()) * amount as f32;
-            *card += Probability::Unknown(value);
-            tmp[i] = value;
-        }
-
-        for (i, value) in tmp.iter().enumerate() {
-            other.cards[i] -= Probability::Unknown(*value);
-        }
-
-        self.size += amount;
-    }
-
     /// Constructs a new Deck from a known `Deck`
-    pub fn from_known(known: KnownDeck) -> Self {
+    pub fn from_known(known: &[usize]) -> Self {
         Self {
-            cards: known.map(Probability::Known),
+            cards: known.iter().copied().map(Probability::Known).collect(),
             size: known.iter().sum(),
         }
     }
@@ -0,0 +1,128 @@
+use crate::{baseline, endgame::{Endgame, EndgameSolver}, error::{GoFishError, Result}, game::{BotGame, Move}, player::{GoFishPlayer, PlayerView}, rules::RuleSet, state::GameState, tournament::Entrant};
+
+/// A player who sees every hand and the order of the deck, as an upper bound for players who can't
+///
+/// Mostly this is only a heuristic: it takes the most cards it can get, preferring asks which complete a set, and
+/// otherwise asks for the rank on top of the deck to get another turn by fishing it. Once the deck is empty it plays
+/// the moves of an [`EndgameSolver`], which are best if every other player sees every hand as well, unless solving
+/// the endgame would go through more than [`MAX_POSITIONS`] positions.
+/// The most positions an [`OracleBot`] searches to solve an endgame, which keeps every move fast
+pub const MAX_POSITIONS: usize = 5_000;
+
+#[derive(Clone)]
+pub struct OracleBot {
+    solver: EndgameSolver,
+}
+
+/// How often a player picked the same move as an [`OracleBot`] would have in its place
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Agreement {
+    /// The number of moves where the player had more than one choice
+    pub decisions: usize,
+    pub agreed: usize,
+}
+
+impl GoFishPlayer for OracleBot {
+    fn choose_move(&mut self, view: &PlayerView) -> Result<Option<Move>> {
+        let Some(state) = view.truth else {
+            return Err(GoFishError::InvalidState("An oracle has to see the whole game".to_string()));
+        };
+
+        best_move(view, state, &mut self.solver)
+    }
+
+    fn sees_everything(&self) -> bool {
+        true
+    }
+}
+
+impl OracleBot {
+    pub fn new(rules: &RuleSet) -> Self {
+        Self {
+            solver: solver(rules),
+        }
+    }
+}
+
+impl Agreement {
+    /// The share of decisions the player agreed with the oracle on, or 0 without any decisions
+    pub fn rate(&self) -> f32 {
+        self.agreed as f32 / self.decisions.max(1) as f32
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.decisions += other.decisions;
+        self.agreed += other.agreed;
+    }
+}
+
+/// The move an [`OracleBot`] would play in a position, solving endgames with `solver` unless they are too large
+pub fn best_move(view: &PlayerView, state: &GameState, solver: &mut EndgameSolver) -> Result<Option<Move>> {
+    if state.stock.is_empty() {
+        if view.hand_sizes[view.seat] == 0 {
+            return Ok(None);
+        }
+
+        match solver.best_query(&Endgame::from_state(state, view.seat, view.rules)) {
+            Ok((query, _)) => return Ok(Some(Move::Query(query))),
+            Err(GoFishError::SearchLimit(_)) => {},
+            Err(err) => return Err(err),
+        }
+    }
+
+    let top = state.stock.last().map(|card| card.rank);
+
+    Ok(baseline::best_move(view, |query| {
+        let held = state.hands[query.player].iter().filter(|card| card.rank == query.card).count();
+        let completes_set = held > 0 && (view.hand[query.card] + held).is_multiple_of(view.rules.book_size);
+
+        (held > 0, completes_set, held, top == Some(query.card), view.hand[query.card])
+    }))
+}
+
+/// Enters an [`OracleBot`] in a [`Tournament`](crate::tournament::Tournament)
+pub fn entrant() -> (String, Entrant<'static>) {
+    ("oracle".to_string(), Box::new(|observer, _| Box::new(OracleBot::new(&observer.rules))))
+}
+
+/// Plays a game like [`BotGame::step`] for up to `max_steps` steps, comparing every decision with the oracle's
+///
+/// Returns the agreement of each seat. The move of a player is found by asking a clone of it first, so players have
+/// to pick the same move when asked twice in the same position, which every player with a seeded random number
+/// generator does.
+pub fn play_measuring_agreement(game: &mut BotGame, max_steps: usize) -> Result<Vec<Agreement>> {
+    let mut agreement = vec![Agreement::default(); game.players.len()];
+    let mut solver = solver(&game.rules);
+
+    for _ in 0..max_steps {
+        if game.over {
+            break;
+        }
+
+        let seat = game.current_player;
+        let mut view = PlayerView::new(seat, &game.state, &game.rules);
+
+        if view.legal_moves().len() > 1 {
+            if game.players[seat].sees_everything() {
+                view.truth = Some(&game.state);
+            }
+
+            let chosen = game.players[seat].clone().choose_move(&view)?;
+
+            agreement[seat].decisions += 1;
+            agreement[seat].agreed += (chosen == best_move(&view, &game.state, &mut solver)?) as usize;
+        }
+
+        game.step()?;
+    }
+
+    Ok(agreement)
+}
+
+/// An [`EndgameSolver`] which only searches as many positions as an [`OracleBot`] does
+fn solver(rules: &RuleSet) -> EndgameSolver {
+    let mut solver = EndgameSolver::new(rules);
+    solver.max_positions = MAX_POSITIONS;
+
+    solver
+}
@@ -0,0 +1,130 @@
+use crate::{error::Result, game::{Move, Query}, observer::{GameObserver, KnownDeck}, rules::{RuleSet, EmptyHandRule}, state::GameState};
+
+/// Anything which can take a seat in a [`BotGame`](crate::game::BotGame)
+///
+/// Players have to be [`Clone`] so that games can be copied for lookahead, see [`BotGame::snapshot`](crate::game::BotGame::snapshot).
+pub trait GoFishPlayer: BoxClone {
+    /// Picks the next move for the player's seat, or `None` if the player can't move
+    fn choose_move(&mut self, view: &PlayerView) -> Result<Option<Move>>;
+
+    /// Informs the player about something which happened in the game
+    ///
+    /// Observations only contain what the player is allowed to know, e.g. the card of another player's
+    /// pickup is hidden.
+    fn observe(&mut self, _observation: &Observation) -> Result<()> {
+        Ok(())
+    }
+
+    /// The player's estimate of the game, if it keeps one
+    fn observer(&self) -> Option<&GameObserver> {
+        None
+    }
+
+    /// Whether the player is shown the whole game in [`PlayerView::truth`], which is only meant for benchmarks
+    fn sees_everything(&self) -> bool {
+        false
+    }
+}
+
+/// Clones a boxed [`GoFishPlayer`], implemented for every player which is [`Clone`]
+pub trait BoxClone {
+    fn box_clone(&self) -> Box<dyn GoFishPlayer>;
+}
+
+impl<T: GoFishPlayer + Clone + 'static> BoxClone for T {
+    fn box_clone(&self) -> Box<dyn GoFishPlayer> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn GoFishPlayer> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+/// Something which happened in a game, using seats to refer to players
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Observation {
+    /// A player picked up a card from the deck. The card is only known to the player who picked it up.
+    Pickup { player: usize, card: Option<usize> },
+    /// A player asked another player for a card
+    Query {
+        player: usize,
+        target: usize,
+        card: usize,
+        /// The amount of cards the player received
+        amount: usize,
+        /// The amount of sets the player placed afterwards
+        books: usize,
+    },
+    /// A player placed sets after picking up
+    Place { player: usize, card: usize, books: usize },
+}
+
+impl Observation {
+    /// Removes everything the player in `seat` isn't allowed to know
+    pub fn redacted_for(&self, seat: usize) -> Self {
+        match *self {
+            Observation::Pickup { player, .. } if player != seat => Observation::Pickup { player, card: None },
+            observation => observation,
+        }
+    }
+}
+
+/// What a player can see of the game when choosing a move
+#[derive(Debug, Clone)]
+pub struct PlayerView<'a> {
+    pub seat: usize,
+    pub hand: KnownDeck,
+    /// The amount of cards in each seat's hand
+    pub hand_sizes: Vec<usize>,
+    /// The amount of cards left in the deck
+    pub deck_size: usize,
+    /// The amount of sets each seat has placed
+    pub books: Vec<usize>,
+    pub rules: &'a RuleSet,
+    /// Where every card is, only for players which [see everything](GoFishPlayer::sees_everything)
+    pub truth: Option<&'a GameState>,
+}
+
+impl<'a> PlayerView<'a> {
+    pub fn new(seat: usize, state: &'a GameState, rules: &'a RuleSet) -> Self {
+        Self {
+            seat,
+            hand: state.hand(seat),
+            hand_sizes: (0..state.hands.len()).map(|seat| state.hand_size(seat)).collect(),
+            deck_size: state.stock_size(),
+            books: state.books(rules),
+            rules,
+            truth: None,
+        }
+    }
+
+    /// Checks whether the player is allowed to make a move
+    ///
+    /// Picking up is only allowed when the player has run out of cards.
+    pub fn is_legal(&self, m: &Move) -> bool {
+        match m {
+            Move::Pickup => self.rules.empty_hand != EmptyHandRule::SitOut
+                && self.deck_size > 0
+                && self.hand_sizes[self.seat] == 0,
+            Move::Query(q) => q.player != self.seat
+                && q.player < self.hand_sizes.len()
+                && q.card < self.rules.ranks
+                && self.hand[q.card] > 0,
+        }
+    }
+
+    /// Returns every move the player is allowed to make
+    pub fn legal_moves(&self) -> Vec<Move> {
+        if self.is_legal(&Move::Pickup) {
+            return vec![Move::Pickup];
+        }
+
+        (0..self.hand_sizes.len())
+            .flat_map(|player| (0..self.rules.ranks).map(move |card| Move::Query(Query { player, card })))
+            .filter(|m| self.is_legal(m))
+            .collect()
+    }
+}
@@ -0,0 +1,131 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use serde::{Serialize, Deserialize};
+
+use crate::{error::Result, result::GameResult};
+
+/// The rating every new player starts with
+pub const INITIAL_RATING: f32 = 1500.;
+/// The default largest change of a rating in one game
+pub const K_FACTOR: f32 = 32.;
+
+/// A multiplayer Elo rating
+///
+/// A game is rated as if every pair of seats played a game against each other, where finishing ahead is a win and
+/// sharing a position is a draw.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rating {
+    pub elo: f32,
+    /// The number of rated games played
+    pub games: usize,
+}
+
+/// Named ratings, for example of saved networks and humans, which can be kept across sessions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ratings {
+    pub k_factor: f32,
+    pub players: BTreeMap<String, Rating>,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self {
+            elo: INITIAL_RATING,
+            games: 0,
+        }
+    }
+}
+
+impl Rating {
+    /// The expected score against `other`, where a win counts 1 and a draw 0.5
+    pub fn expected_score(&self, other: &Rating) -> f32 {
+        1. / (1. + 10f32.powf((other.elo - self.elo) / 400.))
+    }
+}
+
+/// Calculates the change of each seat's rating from the standings of a game
+pub fn rating_changes(ratings: &[Rating], result: &GameResult, k_factor: f32) -> Vec<f32> {
+    let opponents = (ratings.len() - 1).max(1) as f32;
+
+    (0..ratings.len())
+        .map(|seat| {
+            let surprise = (0..ratings.len())
+                .filter(|&other| other != seat)
+                .map(|other| result.score_against(seat, other) - ratings[seat].expected_score(&ratings[other]))
+                .sum::<f32>();
+
+            k_factor * surprise / opponents
+        })
+        .collect()
+}
+
+/// Rates a game, where `ratings` holds the rating of each seat
+pub fn rate(ratings: &mut [Rating], result: &GameResult, k_factor: f32) {
+    let changes = rating_changes(ratings, result, k_factor);
+
+    for (rating, change) in ratings.iter_mut().zip(changes) {
+        rating.elo += change;
+        rating.games += 1;
+    }
+}
+
+impl Default for Ratings {
+    fn default() -> Self {
+        Self::new(K_FACTOR)
+    }
+}
+
+impl Ratings {
+    pub fn new(k_factor: f32) -> Self {
+        Self {
+            k_factor,
+            players: BTreeMap::new(),
+        }
+    }
+
+    /// The rating of a player, or the initial rating if they haven't been rated yet
+    pub fn get(&self, name: &str) -> Rating {
+        self.players.get(name).copied().unwrap_or_default()
+    }
+
+    /// Rates a game, where `names` holds the name of the player in each seat
+    ///
+    /// Returns the change of each seat's rating. A name seated more than once gets the changes of all its seats.
+    pub fn update(&mut self, names: &[impl AsRef<str>], result: &GameResult) -> Vec<f32> {
+        let ratings = names.iter().map(|name| self.get(name.as_ref())).collect::<Vec<_>>();
+        let changes = rating_changes(&ratings, result, self.k_factor);
+
+        for (name, change) in names.iter().zip(&changes) {
+            let rating = self.players.entry(name.as_ref().to_string()).or_default();
+            rating.elo += change;
+            rating.games += 1;
+        }
+
+        changes
+    }
+
+    /// Returns the players ordered from highest to lowest rating
+    pub fn leaderboard(&self) -> Vec<(&str, Rating)> {
+        let mut players = self.players.iter().map(|(name, &rating)| (name.as_str(), rating)).collect::<Vec<_>>();
+        players.sort_by(|a, b| b.1.elo.total_cmp(&a.1.elo));
+
+        players
+    }
+
+    /// Saves the ratings as JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    /// Loads ratings saved with [`Ratings::save`], or starts new ones if the file doesn't exist yet
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
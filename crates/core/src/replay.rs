@@ -0,0 +1,109 @@
+use std::{fs, path::Path};
+
+use serde::{Serialize, Deserialize};
+use crate::{error::{GoFishError, Result}, card, event::GameEvent, observer::GameObserver, player::Observation, rules::RuleSet, stats::GameStats};
+
+/// A recording of a whole game which can be saved and replayed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub rules: RuleSet,
+    pub players: usize,
+    pub events: Vec<GameEvent>,
+}
+
+impl Replay {
+    /// Saves the replay as JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, serde_json::to_string(self)?)?;
+
+        Ok(())
+    }
+
+    /// Loads a replay saved with [`Replay::save`]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Converts the events into the observations the players received
+    ///
+    /// The observations aren't redacted, see [`Observation::redacted_for`].
+    pub fn observations(&self) -> Vec<Observation> {
+        observations(&self.events, &self.rules)
+    }
+
+    /// Reconstructs the observer of every seat after the first `events` events
+    pub fn observers_after(&self, events: usize) -> Result<Vec<GameObserver>> {
+        let events = &self.events[..events.min(self.events.len())];
+
+        let mut observers = (0..self.players).map(|seat| {
+            events.iter()
+                .find_map(|event| match event {
+                    GameEvent::Deal { seat: dealt, hand } if *dealt == seat => Some(GameObserver::new(self.players - 1, Some(card::rank_counts(hand, self.rules.ranks)), seat, &self.rules)),
+                    _ => None,
+                })
+                .ok_or(GoFishError::MissingDeal(seat))
+        }).collect::<Result<Vec<_>>>()?;
+
+        for observation in observations(events, &self.rules) {
+            for observer in observers.iter_mut() {
+                observer.observe(&observation.redacted_for(observer.id))?;
+            }
+        }
+
+        Ok(observers)
+    }
+
+    /// Collects statistics about the recorded game
+    pub fn stats(&self) -> GameStats {
+        GameStats::from_events(self.players, &self.events, &self.rules)
+    }
+
+    /// Reconstructs the observer of every seat at the end of the game
+    pub fn observers(&self) -> Result<Vec<GameObserver>> {
+        self.observers_after(self.events.len())
+    }
+}
+
+fn observations(events: &[GameEvent], rules: &RuleSet) -> Vec<Observation> {
+    let mut observations = Vec::new();
+    // Queries are spread over an ask, an optional give and an optional place
+    let mut query = None;
+
+    for event in events {
+        match event {
+            &GameEvent::Ask { player, target, card } => {
+                observations.extend(query.take());
+                query = Some(Observation::Query { player, target, card, amount: 0, books: 0 });
+            },
+            GameEvent::Give { cards, .. } => {
+                if let Some(Observation::Query { amount, .. }) = &mut query {
+                    *amount = cards.len();
+                }
+            },
+            &GameEvent::Place { player, ref cards } => {
+                let card = cards.first().map_or(0, |card| card.rank);
+                let placed = cards.len() / rules.book_size;
+
+                match &mut query {
+                    Some(Observation::Query { player: asker, card: asked, amount, books, .. }) if *asker == player && *asked == card && *amount > 0 => {
+                        *books = placed;
+                        observations.extend(query.take());
+                    },
+                    _ => {
+                        observations.extend(query.take());
+                        observations.push(Observation::Place { player, card, books: placed });
+                    },
+                }
+            },
+            &GameEvent::Draw { player, card } => {
+                observations.extend(query.take());
+                observations.push(Observation::Pickup { player, card: Some(card.rank) });
+            },
+            GameEvent::Deal { .. } | GameEvent::GameOver { .. } => observations.extend(query.take()),
+        }
+    }
+
+    observations.extend(query.take());
+
+    observations
+}
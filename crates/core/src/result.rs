@@ -0,0 +1,72 @@
+use serde::{Serialize, Deserialize};
+
+/// The standings of a game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameResult {
+    /// The number of books each seat has placed
+    pub books: Vec<usize>,
+    /// The position each seat finished in, where `0` is first place. Tied seats share a position.
+    pub positions: Vec<usize>,
+}
+
+impl GameResult {
+    /// Constructs the standings from the number of books each seat has placed
+    pub fn from_books(books: Vec<usize>) -> Self {
+        let positions = books.iter()
+            .map(|&placed| books.iter().filter(|&&other| other > placed).count())
+            .collect();
+
+        Self {
+            books,
+            positions,
+        }
+    }
+
+    /// Returns the seats ordered from first to last place
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut seats = (0..self.books.len()).collect::<Vec<_>>();
+        seats.sort_by_key(|&seat| self.positions[seat]);
+
+        seats
+    }
+
+    /// Returns every seat sharing first place
+    pub fn winners(&self) -> Vec<usize> {
+        (0..self.books.len()).filter(|&seat| self.positions[seat] == 0).collect()
+    }
+
+    /// Returns the winning seat, or `None` if first place is tied
+    pub fn winner(&self) -> Option<usize> {
+        match self.winners().as_slice() {
+            &[seat] => Some(seat),
+            _ => None,
+        }
+    }
+
+    /// Scores `seat` against `other` as if they played a game against each other: 1 for finishing ahead, 0.5 for
+    /// sharing a position and 0 for finishing behind
+    pub fn score_against(&self, seat: usize, other: usize) -> f32 {
+        match self.positions[seat].cmp(&self.positions[other]) {
+            std::cmp::Ordering::Less => 1.,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Greater => 0.,
+        }
+    }
+
+    /// Scores every seat by the share of the other seats it finished ahead of, counting ties as half
+    pub fn scores(&self) -> Vec<f32> {
+        let seats = self.positions.len();
+
+        (0..seats)
+            .map(|seat| (0..seats)
+                .filter(|&other| other != seat)
+                .map(|other| self.score_against(seat, other))
+                .sum::<f32>() / (seats - 1).max(1) as f32)
+            .collect()
+    }
+
+    /// Whether more than one seat shares first place
+    pub fn is_tie(&self) -> bool {
+        self.winners().len() > 1
+    }
+}
@@ -0,0 +1,102 @@
+use serde::{Serialize, Deserialize};
+
+use crate::error::{GoFishError, Result};
+
+/// The number of different cards in a standard deck
+pub const DECK_SIZE: usize = 13; // We only need to know the value of the card, not the suit.
+/// The number of card suits in a standard deck
+pub const SUITS: usize = 4;
+
+/// The house rules a game is played with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleSet {
+    /// The number of different card values in the deck
+    pub ranks: usize,
+    /// The number of cards of each value in the deck, e.g. 8 for a double deck
+    pub suits: usize,
+    /// The number of cards each player starts with in a game of up to `large_game_players - 1` players
+    #[serde(rename = "smallGameHand")]
+    pub small_game_hand: usize,
+    /// The number of cards each player starts with in a game of at least `large_game_players` players
+    #[serde(rename = "largeGameHand")]
+    pub large_game_hand: usize,
+    /// The number of players from which on `large_game_hand` is used
+    #[serde(rename = "largeGamePlayers")]
+    pub large_game_players: usize,
+    /// The number of cards of the same value which make up a set. Has to divide `suits`.
+    #[serde(rename = "bookSize")]
+    pub book_size: usize,
+    /// Whether a player who received cards from a query gets another turn
+    #[serde(rename = "continueOnSuccess")]
+    pub continue_on_success: bool,
+    /// What a player does when they start their turn without any cards
+    #[serde(rename = "emptyHand")]
+    pub empty_hand: EmptyHandRule,
+}
+
+/// What happens when a player has to take their turn without any cards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmptyHandRule {
+    /// The player picks up a single card from the deck and continues their turn
+    Draw,
+    /// The player picks up until they are back at their starting hand size and continues their turn
+    Refill,
+    /// The player is out of the game
+    SitOut,
+}
+
+impl RuleSet {
+    /// The number of cards each player starts with in a game of `players` players
+    pub fn starting_cards(&self, players: usize) -> usize {
+        if players >= self.large_game_players {
+            self.large_game_hand
+        } else {
+            self.small_game_hand
+        }
+    }
+
+    /// The number of sets which can be placed over the whole game
+    pub fn books(&self) -> usize {
+        self.ranks * self.books_per_card()
+    }
+
+    /// The number of sets of each card which can be placed over the whole game
+    pub fn books_per_card(&self) -> usize {
+        self.suits / self.book_size
+    }
+
+    /// The number of cards in the deck
+    pub fn deck_size(&self) -> usize {
+        self.ranks * self.suits
+    }
+
+    /// Checks that whole sets can be made from the cards of every rank
+    pub fn validate(&self) -> Result<()> {
+        if self.book_size == 0 {
+            return Err(GoFishError::InvalidState("Sets have to be made of at least one card".to_string()));
+        }
+
+        if !self.suits.is_multiple_of(self.book_size) {
+            return Err(GoFishError::InvalidState(format!("{} cards of each rank can't be split into sets of {}", self.suits, self.book_size)));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for RuleSet {
+    /// The standard rules: a 52 card deck, 7 cards for 2-3 players and 5 for more, sets of four
+    /// and another turn after a successful query
+    fn default() -> Self {
+        Self {
+            ranks: DECK_SIZE,
+            suits: SUITS,
+            small_game_hand: 7,
+            large_game_hand: 5,
+            large_game_players: 4,
+            book_size: SUITS,
+            continue_on_success: true,
+            empty_hand: EmptyHandRule::Draw,
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use serde::{Serialize, Deserialize};
+use crate::{card::{self, Card}, observer::KnownDeck, rules::RuleSet, error::{GoFishError, Result}};
+
+/// Where every card of a game actually is
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameState {
+    /// The cards in each seat's hand
+    pub hands: Vec<Vec<Card>>,
+    /// The cards left in the deck, where the last card is on top
+    pub stock: Vec<Card>,
+    /// The cards each seat has placed in sets
+    pub placed: Vec<Vec<Card>>,
+    /// The number of different ranks in the deck
+    pub ranks: usize,
+}
+
+impl GameState {
+    /// Constructs a state where nothing has been placed yet
+    pub fn new(hands: Vec<Vec<Card>>, stock: Vec<Card>, ranks: usize) -> Self {
+        Self {
+            ranks,
+            placed: vec![Vec::new(); hands.len()],
+            hands,
+            stock,
+        }
+    }
+
+    /// The number of cards of each rank in a seat's hand
+    pub fn hand(&self, seat: usize) -> KnownDeck {
+        card::rank_counts(&self.hands[seat], self.ranks)
+    }
+
+    /// The number of cards of each rank left in the deck
+    pub fn stock_counts(&self) -> KnownDeck {
+        card::rank_counts(&self.stock, self.ranks)
+    }
+
+    /// The number of cards of each rank placed by everyone
+    pub fn placed_counts(&self) -> KnownDeck {
+        card::rank_counts(&self.placed.concat(), self.ranks)
+    }
+
+    /// The number of cards in a seat's hand
+    pub fn hand_size(&self, seat: usize) -> usize {
+        self.hands[seat].len()
+    }
+
+    /// The number of cards left in the deck
+    pub fn stock_size(&self) -> usize {
+        self.stock.len()
+    }
+
+    /// The number of sets each seat has placed
+    pub fn books(&self, rules: &RuleSet) -> Vec<usize> {
+        self.placed.iter().map(|placed| placed.len() / rules.book_size).collect()
+    }
+
+    /// The number of sets placed by everyone
+    pub fn total_books(&self, rules: &RuleSet) -> usize {
+        self.books(rules).iter().sum()
+    }
+
+    /// Takes every card of a rank out of a seat's hand
+    pub fn take_all(&mut self, seat: usize, rank: usize) -> Vec<Card> {
+        let (taken, kept) = self.hands[seat].iter().partition(|card| card.rank == rank);
+        self.hands[seat] = kept;
+
+        taken
+    }
+
+    /// Takes the top card off the deck
+    pub fn draw(&mut self) -> Option<Card> {
+        self.stock.pop()
+    }
+
+    /// Adds cards to a seat's hand, placing and returning the cards of any completed sets
+    pub fn add_to_hand(&mut self, seat: usize, cards: &[Card], rules: &RuleSet) -> Vec<Card> {
+        let hand = &mut self.hands[seat];
+        hand.extend_from_slice(cards);
+        hand.sort();
+
+        let counts = card::rank_counts(hand, self.ranks);
+        let mut placed = Vec::new();
+
+        hand.retain(|card| {
+            let placeable = counts[card.rank] - counts[card.rank] % rules.book_size;
+
+            if placed.iter().filter(|other: &&Card| other.rank == card.rank).count() < placeable {
+                placed.push(*card);
+                false
+            } else {
+                true
+            }
+        });
+
+        self.placed[seat].extend_from_slice(&placed);
+
+        placed
+    }
+
+    /// Checks that no card has been lost or duplicated and that sets have been placed correctly
+    pub fn validate(&self, rules: &RuleSet) -> Result<()> {
+        rules.validate()?;
+
+        if self.ranks != rules.ranks {
+            return Err(GoFishError::InvalidState(format!("The game has {} ranks instead of {}", self.ranks, rules.ranks)));
+        }
+
+        if self.hands.len() != self.placed.len() {
+            return Err(GoFishError::InvalidState(format!("{} hands but {} seats with placed cards", self.hands.len(), self.placed.len())));
+        }
+
+        let mut cards = self.stock.iter()
+            .chain(self.hands.iter().flatten())
+            .chain(self.placed.iter().flatten())
+            .copied()
+            .collect::<Vec<_>>();
+        cards.sort();
+
+        if cards != Card::full_deck(rules) {
+            return Err(GoFishError::InvalidState(format!("The game has {} cards which don't make up a full deck", cards.len())));
+        }
+
+        for seat in 0..self.hands.len() {
+            let hand = self.hand(seat);
+            let placed = card::rank_counts(&self.placed[seat], self.ranks);
+
+            for rank in 0..self.ranks {
+                if hand[rank] >= rules.book_size {
+                    return Err(GoFishError::InvalidState(format!("Seat {seat} holds {} cards of rank {rank} without placing them", hand[rank])));
+                }
+
+                if !placed[rank].is_multiple_of(rules.book_size) {
+                    return Err(GoFishError::InvalidState(format!("Seat {seat} placed {} cards of rank {rank}, which isn't a whole number of sets", placed[rank])));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
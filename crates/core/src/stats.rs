@@ -0,0 +1,167 @@
+use serde::{Serialize, Deserialize};
+use crate::{event::GameEvent, rules::RuleSet};
+
+/// Statistics about how one or more games played out
+///
+/// Statistics of several games can be combined with [`GameStats::merge`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GameStats {
+    /// The number of games which were played
+    pub games: usize,
+    /// The number of turns, where a turn lasts until the next player starts acting
+    pub turns: usize,
+    /// The statistics of each seat
+    pub seats: Vec<SeatStats>,
+    /// The turn each set was placed in, where sets placed from the starting hands count as turn 0
+    #[serde(rename = "bookTurns")]
+    pub book_turns: Vec<usize>,
+}
+
+/// Statistics about how a single seat played
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeatStats {
+    pub asks: usize,
+    /// The number of asks which got at least one card
+    #[serde(rename = "successfulAsks")]
+    pub successful_asks: usize,
+    /// The number of cards drawn after a failed ask
+    #[serde(rename = "goFishDraws")]
+    pub go_fish_draws: usize,
+    /// The number of cards drawn because of an empty hand
+    pub pickups: usize,
+    /// The number of cards received from other players
+    #[serde(rename = "cardsGained")]
+    pub cards_gained: usize,
+    /// The number of cards given to other players
+    #[serde(rename = "cardsLost")]
+    pub cards_lost: usize,
+    pub books: usize,
+    /// The most successful asks in a row
+    #[serde(rename = "longestStreak")]
+    pub longest_streak: usize,
+}
+
+impl GameStats {
+    /// Collects the statistics of a game from its events
+    pub fn from_events(players: usize, events: &[GameEvent], rules: &RuleSet) -> Self {
+        let mut stats = Self {
+            games: 1,
+            seats: vec![SeatStats::default(); players],
+            ..Default::default()
+        };
+
+        let mut actor = None;
+        // The player whose ask failed, as their next draw is a go fish draw
+        let mut fishing = None;
+        let mut streak = 0;
+
+        for event in events {
+            let acting = match *event {
+                GameEvent::Ask { player, .. } | GameEvent::Draw { player, .. } => Some(player),
+                _ => None,
+            };
+
+            if acting.is_some() && acting != actor {
+                actor = acting;
+                stats.turns += 1;
+                streak = 0;
+            }
+
+            match *event {
+                GameEvent::Ask { player, .. } => {
+                    // The previous ask failed without anything left to draw
+                    if fishing.is_some() {
+                        streak = 0;
+                    }
+
+                    stats.seats[player].asks += 1;
+                    fishing = Some(player);
+                },
+                GameEvent::Give { player, target, ref cards } => {
+                    let seat = &mut stats.seats[target];
+                    seat.successful_asks += 1;
+                    seat.cards_gained += cards.len();
+                    stats.seats[player].cards_lost += cards.len();
+                    fishing = None;
+
+                    streak += 1;
+                    stats.seats[target].longest_streak = stats.seats[target].longest_streak.max(streak);
+                },
+                GameEvent::Draw { player, .. } => {
+                    if fishing.take() == Some(player) {
+                        stats.seats[player].go_fish_draws += 1;
+                        streak = 0;
+                    } else {
+                        stats.seats[player].pickups += 1;
+                    }
+                },
+                GameEvent::Place { player, ref cards } => {
+                    let books = cards.len() / rules.book_size;
+
+                    stats.seats[player].books += books;
+                    stats.book_turns.extend(std::iter::repeat_n(stats.turns, books));
+                },
+                GameEvent::Deal { .. } | GameEvent::GameOver { .. } => {},
+            }
+        }
+
+        stats
+    }
+
+    /// Adds the statistics of other games to these
+    ///
+    /// Seats are combined by their position, so games with fewer players only add to the first seats.
+    pub fn merge(&mut self, other: &GameStats) {
+        self.games += other.games;
+        self.turns += other.turns;
+        self.book_turns.extend_from_slice(&other.book_turns);
+
+        if self.seats.len() < other.seats.len() {
+            self.seats.resize(other.seats.len(), SeatStats::default());
+        }
+
+        for (seat, other) in self.seats.iter_mut().zip(&other.seats) {
+            seat.merge(other);
+        }
+    }
+
+    /// The statistics of every seat added together
+    pub fn total(&self) -> SeatStats {
+        let mut total = SeatStats::default();
+
+        for seat in self.seats.iter() {
+            total.merge(seat);
+        }
+
+        total
+    }
+
+    /// The average number of turns per game
+    pub fn average_turns(&self) -> f32 {
+        self.turns as f32 / self.games.max(1) as f32
+    }
+
+    /// The average turn a set was placed in
+    pub fn average_book_turn(&self) -> f32 {
+        self.book_turns.iter().sum::<usize>() as f32 / self.book_turns.len().max(1) as f32
+    }
+}
+
+impl SeatStats {
+    /// The fraction of asks which got at least one card, or 0 without any asks
+    pub fn success_rate(&self) -> f32 {
+        self.successful_asks as f32 / self.asks.max(1) as f32
+    }
+
+    /// Adds the statistics of another seat to these
+    pub fn merge(&mut self, other: &SeatStats) {
+        self.asks += other.asks;
+        self.successful_asks += other.successful_asks;
+        self.go_fish_draws += other.go_fish_draws;
+        self.pickups += other.pickups;
+        self.cards_gained += other.cards_gained;
+        self.cards_lost += other.cards_lost;
+        self.books += other.books;
+        self.longest_streak = self.longest_streak.max(other.longest_streak);
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt;
+
+use crate::{error::Result, game::{Bot, BotGame}, neural_network::NeuralNetwork, observer::GameObserver, oracle::{self, Agreement}, player::GoFishPlayer, rating::{Rating, Ratings}, rules::RuleSet, util};
+
+/// Seats a player for an entrant of a [`Tournament`] from the seat's [`GameObserver`] and the seed of the deal
+pub type Entrant<'a> = Box<dyn Fn(GameObserver, u64) -> Box<dyn GoFishPlayer> + 'a>;
+
+/// A round robin where every group of `game_size` entrants plays every deal once from every seat
+pub struct Tournament {
+    pub rules: RuleSet,
+    pub game_size: usize,
+    /// The number of deals every group plays
+    pub deals: usize,
+    /// The number of steps after which an unfinished game is scored as it stands
+    pub max_steps: usize,
+    pub seed: u64,
+    /// Whether to compare every decision with an [`OracleBot`](crate::oracle::OracleBot), which takes about twice as long
+    pub oracle_agreement: bool,
+}
+
+/// The results of a [`Tournament`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentResult {
+    pub names: Vec<String>,
+    /// `head_to_head[i][j]` is how entrant `i` did against entrant `j` in the games they played together
+    pub head_to_head: Vec<Vec<Record>>,
+    /// How often each entrant won their games
+    pub overall: Vec<Record>,
+    /// The rating of each entrant after the tournament
+    pub ratings: Vec<Rating>,
+    /// How often each entrant agreed with the oracle, if it was compared with the oracle at all
+    pub agreement: Vec<Agreement>,
+}
+
+/// A score out of a number of games, where a game counts 1 for a win and is shared for a tie
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Record {
+    pub games: usize,
+    pub score: f32,
+}
+
+/// The z-score of a 95% confidence interval
+const Z: f32 = 1.96;
+
+impl Tournament {
+    pub fn new(rules: RuleSet, game_size: usize, deals: usize, max_steps: usize, seed: u64) -> Self {
+        Self {
+            rules,
+            game_size,
+            deals,
+            max_steps,
+            seed,
+            oracle_agreement: false,
+        }
+    }
+
+    /// Plays the tournament between named entrants and rates every game by the entrants' names
+    ///
+    /// The same deals are used for every group, so the result only depends on the seed and the ratings passed in.
+    pub fn run(&self, entrants: &[(String, Entrant)], ratings: &mut Ratings) -> Result<TournamentResult> {
+        let n = entrants.len();
+        let mut head_to_head = vec![vec![Record::default(); n]; n];
+        let mut overall = vec![Record::default(); n];
+        let mut agreement = vec![Agreement::default(); n];
+
+        for group in combinations(n, self.game_size) {
+            for deal in 0..self.deals {
+                let seed = util::derive_seed(self.seed, deal as u64);
+
+                for rotation in 0..self.game_size {
+                    // The entrant in each seat
+                    let seated = (0..self.game_size).map(|seat| group[(seat + rotation) % self.game_size]).collect::<Vec<_>>();
+                    let mut game = BotGame::new_rand_with(self.game_size, &self.rules, seed, |observer| (entrants[seated[observer.id]].1)(observer, seed));
+
+                    if self.oracle_agreement {
+                        for (seat, seat_agreement) in oracle::play_measuring_agreement(&mut game, self.max_steps)?.iter().enumerate() {
+                            agreement[seated[seat]].merge(seat_agreement);
+                        }
+                    } else {
+                        for _ in 0..self.max_steps {
+                            if game.over {
+                                break;
+                            }
+
+                            game.step()?;
+                        }
+                    }
+
+                    let standings = game.standings();
+                    let winners = standings.winners();
+                    ratings.update(&seated.iter().map(|&entrant| &entrants[entrant].0).collect::<Vec<_>>(), &standings);
+
+                    for (seat, &entrant) in seated.iter().enumerate() {
+                        overall[entrant].add(if winners.contains(&seat) { 1. / winners.len() as f32 } else { 0. });
+
+                        for (other_seat, &other) in seated.iter().enumerate().filter(|&(other_seat, _)| other_seat != seat) {
+                            head_to_head[entrant][other].add(standings.score_against(seat, other_seat));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(TournamentResult {
+            names: entrants.iter().map(|(name, _)| name.clone()).collect(),
+            head_to_head,
+            overall,
+            ratings: entrants.iter().map(|(name, _)| ratings.get(name)).collect(),
+            agreement,
+        })
+    }
+}
+
+/// Enters a [`Bot`] playing with `network`
+pub fn bot_entrant(network: &NeuralNetwork) -> Entrant<'_> {
+    Box::new(move |observer, _| Box::new(Bot::new(observer, network)))
+}
+
+impl Record {
+    fn add(&mut self, score: f32) {
+        self.games += 1;
+        self.score += score;
+    }
+
+    /// The average score per game, or 0 without any games
+    pub fn win_rate(&self) -> f32 {
+        self.score / self.games.max(1) as f32
+    }
+
+    /// The 95% Wilson score interval of the win rate
+    pub fn confidence_interval(&self) -> (f32, f32) {
+        if self.games == 0 {
+            return (0., 1.);
+        }
+
+        let n = self.games as f32;
+        let p = self.win_rate();
+        let denominator = 1. + Z * Z / n;
+        let center = (p + Z * Z / (2. * n)) / denominator;
+        let margin = Z * (p * (1. - p) / n + Z * Z / (4. * n * n)).sqrt() / denominator;
+
+        ((center - margin).max(0.), (center + margin).min(1.))
+    }
+}
+
+impl fmt::Display for TournamentResult {
+    /// Writes a cross table of the win rates in percent, each followed by the half width of its confidence interval,
+    /// along with the ratings and the agreement with the oracle
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labels = self.names.iter().enumerate().map(|(i, name)| format!("{i} {name}")).collect::<Vec<_>>();
+        let width = labels.iter().map(|label| label.len()).max().unwrap_or(0);
+        let percent = |record: &Record| {
+            let (low, high) = record.confidence_interval();
+            format!("{:.0}±{:.0}", record.win_rate() * 100., (high - low) * 50.)
+        };
+
+        write!(f, "{:width$}", "")?;
+
+        for i in 0..self.names.len() {
+            write!(f, " {i:>8}")?;
+        }
+
+        write!(f, " {:>8} {:>6}", "Overall", "Elo")?;
+
+        let oracle = self.agreement.iter().any(|agreement| agreement.decisions > 0);
+
+        if oracle {
+            write!(f, " {:>6}", "Oracle")?;
+        }
+
+        writeln!(f)?;
+
+        for (i, label) in labels.iter().enumerate() {
+            write!(f, "{label:width$}")?;
+
+            for (j, record) in self.head_to_head[i].iter().enumerate() {
+                match i == j {
+                    true => write!(f, " {:>8}", "-")?,
+                    false => write!(f, " {:>8}", percent(record))?,
+                }
+            }
+
+            write!(f, " {:>8} {:>6.0}", percent(&self.overall[i]), self.ratings[i].elo)?;
+
+            if oracle {
+                write!(f, " {:>5.0}%", self.agreement[i].rate() * 100.)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Every way to choose `k` of `n` indices, in lexicographic order
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+
+    (k - 1..n)
+        .flat_map(|last| combinations(last, k - 1).into_iter().map(move |mut group| {
+            group.push(last);
+            group
+        }))
+        .collect()
+}
@@ -1,8 +1,6 @@
 use rand::{Rng, seq::SliceRandom};
-use anyhow::Result;
-use rayon::{slice::ParallelSliceMut, prelude::ParallelIterator};
 
-use crate::{game::{BotGame, INPUTS_PER_UNKNOWN_CARD}, neural_network::NeuralNetwork, observer::DECK_SIZE, util};
+use crate::{error::Result, game::{Bot, BotGame}, neural_network::{Activation, NeuralNetwork}, rating::{self, Rating}, rules::RuleSet, stats::GameStats, util};
 
 pub struct BotTrainer {
     pub players: Vec<Agent>,
@@ -10,13 +8,22 @@ pub struct BotTrainer {
     pub reproduction_fraction: f32,
     pub evaluation_games: usize,
     pub game_size: usize,
+    /// The number of steps after which an unfinished game is cut off
     pub max_turns: usize,
+    pub rules: RuleSet,
+    /// The largest change of an agent's rating in one game
+    pub k_factor: f32,
+    /// Statistics of the games played to evaluate the current generation
+    pub stats: GameStats,
 }
 
 #[derive(Clone)]
 pub struct Agent {
     pub network: NeuralNetwork,
-    pub fitness: f32
+    /// The agent's rating after the last evaluation
+    pub fitness: f32,
+    /// Kept for as long as the agent survives, so it settles over the generations
+    pub rating: Rating,
 }
 
             // network: NeuralNetwork::new_rand(
@@ -26,15 +33,21 @@ pub struct Agent {
             // ),
 
 impl BotTrainer {
-    pub fn new(agents: usize, reproduction_fraction: f32, evaluation_games: usize, game_size: usize, max_turns: usize, rng: &mut impl Rng) -> Self {
+    /// Creates a population of random agents
+    ///
+    /// The same seed always results in the same population.
+    pub fn new(agents: usize, reproduction_fraction: f32, evaluation_games: usize, game_size: usize, max_turns: usize, rules: RuleSet, seed: u64) -> Self {
+        let rng = &mut util::rng_from_seed(seed);
+
         Self {
             players: (0..agents).map(|_| Agent {
                 fitness: 0.0,
+                rating: Rating::default(),
                 network: NeuralNetwork::new_rand(
-                    game_size * DECK_SIZE * INPUTS_PER_UNKNOWN_CARD + DECK_SIZE + 1,
+                    Bot::inputs(game_size, &rules),
                     &[
-                        ((game_size - 1) * DECK_SIZE, util::ac_tanh),
-                        ((game_size - 1) * DECK_SIZE, util::ac_softmax),
+                        (Bot::outputs(game_size, &rules), Activation::Tanh),
+                        (Bot::outputs(game_size, &rules), Activation::Softmax),
                     ],
                     rng
                 )
@@ -44,10 +57,18 @@ impl BotTrainer {
             evaluation_games,
             game_size,
             max_turns,
+            rules,
+            k_factor: rating::K_FACTOR,
+            stats: GameStats::default(),
         }
     }
 
-    pub fn step(&mut self, rng: &mut impl Rng) -> Result<()> {
+    /// Breeds the next generation and evaluates it
+    ///
+    /// Every game is dealt with a seed derived from `seed`, so the same seed always results in the same generation.
+    pub fn step(&mut self, seed: u64) -> Result<()> {
+        let rng = &mut util::rng_from_seed(seed);
+
         // Selection
         self.players.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap());
 
@@ -67,31 +88,39 @@ impl BotTrainer {
 
             self.players[i].network = parent_1.network.cross(&parent_2.network, rng);
             self.players[i].network.mutate(rng);
+            self.players[i].rating = Rating::default();
         }
 
-        // Reset fitness
-        for player in self.players.iter_mut() {
-            player.fitness = 0.;
-        }
+        self.stats = GameStats::default();
 
         // Evaluation
-        for _ in 0..self.evaluation_games {
+        for i in 0..self.evaluation_games {
             self.players.shuffle(rng);
-            self.players.chunks_mut(self.game_size).for_each(|chunk| {
-                let mut rng = rand::thread_rng();
-                let mut game = BotGame::new_rand(chunk.iter().map(|a| a.network.clone()).collect::<Vec<_>>().as_slice(), &mut rng);
+            for (j, chunk) in self.players.chunks_mut(self.game_size).enumerate() {
+                let game_seed = util::derive_seed(seed, (i * self.agents + j) as u64);
+                let mut game = BotGame::new_rand(chunk.iter().map(|a| a.network.clone()).collect::<Vec<_>>().as_slice(), &self.rules, game_seed);
 
                 for _ in 0..self.max_turns {
-                    game.step(&mut rng).unwrap();
+                    game.step()?;
                     if game.over {
                         break;
                     }
                 }
 
-                for (j, player) in chunk.iter_mut().enumerate() {
-                    player.fitness += game.players[j].number_placed as f32;
+                self.stats.merge(&game.stats());
+
+                let mut ratings = chunk.iter().map(|player| player.rating).collect::<Vec<_>>();
+                rating::rate(&mut ratings, &game.standings(), self.k_factor);
+
+                for (player, rating) in chunk.iter_mut().zip(ratings) {
+                    player.rating = rating;
                 }
-            })
+            }
+        }
+
+        // Unlike counting the players beaten, a rating accounts for how strong they were
+        for player in self.players.iter_mut() {
+            player.fitness = player.rating.elo;
         }
 
         Ok(())
@@ -1,9 +1,36 @@
 use std::ops::RangeInclusive;
 
-use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 
 use crate::neural_network::Neuron;
 
+/// The random number generator used wherever results need to be reproducible
+pub type GameRng = ChaCha8Rng;
+
+/// Creates a [`GameRng`] from a seed
+pub fn rng_from_seed(seed: u64) -> GameRng {
+    GameRng::seed_from_u64(seed)
+}
+
+/// Derives an independent seed from a seed and an index, e.g. for each game played with one seed
+pub fn derive_seed(seed: u64, index: u64) -> u64 {
+    // SplitMix64
+    let mut z = seed.wrapping_add(index.wrapping_add(1).wrapping_mul(0x9E3779B97F4A7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// The number of ways to pick `k` of `n` things, which is 0 if `k > n`
+pub fn binomial(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.;
+    }
+
+    (0..k).map(|i| (n - i) as f64 / (i + 1) as f64).product()
+}
+
 pub fn lerp(range: RangeInclusive<f32>, progress: f32) -> f32 { 
     let r = range.end() - range.start();
     range.start() + progress * r
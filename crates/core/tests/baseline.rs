@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use fish_core::{baseline::{self, CountingBot, LikelyHolderBot, RandomBot}, game::{BotGame, Move, Query}, player::{GoFishPlayer, Observation, PlayerView}, rating::Ratings, rules::RuleSet, tournament::Tournament};
+
+    #[test]
+    fn baselines_play_whole_games() {
+        let rules = RuleSet::default();
+        let entrants = baseline::entrants();
+        let result = Tournament::new(rules, 3, 5, 1000, 0).run(&entrants, &mut Ratings::default()).unwrap();
+
+        // Three groups of three with each entrant, five deals, three seats
+        assert!(result.overall.iter().all(|record| record.games == 45));
+
+        // Counting cards beats guessing
+        let (random, counting) = (0, 3);
+        assert_eq!(result.names[counting], "counting");
+        assert!(result.head_to_head[counting][random].win_rate() > 0.5);
+    }
+
+    #[test]
+    fn counting_asks_for_known_cards() {
+        let rules = RuleSet::default();
+        let game = BotGame::new_rand_with(3, &rules, 0, |observer| Box::new(CountingBot::new(observer)));
+        let view = PlayerView::new(0, &game.state, &rules);
+
+        // Seat 0 sees seat 1 get a card of its least held rank from seat 2
+        let hand = game.state.hand(0);
+        let card = (0..rules.ranks).filter(|&card| hand[card] > 0).min_by_key(|&card| hand[card]).unwrap();
+
+        let mut bot = CountingBot::new(game.players[0].observer().unwrap().clone());
+        bot.observe(&Observation::Query { player: 1, target: 2, card, amount: 1, books: 0 }).unwrap();
+
+        assert_eq!(bot.choose_move(&view).unwrap(), Some(Move::Query(Query { player: 1, card })));
+    }
+
+    #[test]
+    fn likely_holders_break_ties_in_order() {
+        let rules = RuleSet::default();
+
+        for seed in 0..5 {
+            let game = BotGame::new_rand_with(3, &rules, seed, |observer| Box::new(LikelyHolderBot::new(observer)));
+            let view = PlayerView::new(0, &game.state, &rules);
+
+            if view.hand_sizes[1] != view.hand_sizes[2] {
+                continue;
+            }
+
+            // Before anything has been observed, the ranks held least are the likeliest to be in the other hands,
+            // which tie between players with as many cards
+            let hand = game.state.hand(0);
+            let card = (0..rules.ranks).filter(|&card| hand[card] > 0).min_by_key(|&card| hand[card]).unwrap();
+
+            let mut bot = LikelyHolderBot::new(game.players[0].observer().unwrap().clone());
+            assert_eq!(bot.choose_move(&view).unwrap(), Some(Move::Query(Query { player: 1, card })));
+        }
+    }
+
+    #[test]
+    fn random_bots_are_reproducible() {
+        let play = || {
+            let mut game = BotGame::new_rand_with(3, &RuleSet::default(), 1, |observer| Box::new(RandomBot::new(observer.id as u64)));
+
+            for _ in 0..200 {
+                game.step().unwrap();
+            }
+
+            game.events
+        };
+
+        assert_eq!(play(), play());
+    }
+
+    #[test]
+    fn random_entrants_depend_on_the_deal() {
+        let entrants = baseline::entrants();
+        let (name, random) = &entrants[0];
+        assert_eq!(name, "random");
+
+        // The same cards are dealt every time, so only the seed the entrant is given changes the game
+        let play = |seed| {
+            let mut game = BotGame::new_rand_with(3, &RuleSet::default(), 1, |observer| random(observer, seed));
+
+            for _ in 0..200 {
+                game.step().unwrap();
+            }
+
+            game.events
+        };
+
+        assert_eq!(play(0), play(0));
+        assert_ne!(play(0), play(1));
+    }
+}
@@ -0,0 +1,208 @@
+#[cfg(test)]
+mod tests {
+    use fish_core::{baseline::CountingBot, game::BotGame, observer::{GameObserver, World}, player::Observation, probability::Probability, rules::RuleSet, util};
+
+    #[test]
+    fn estimates_fit_the_real_hands() {
+        for seed in 0..5 {
+            let mut game = BotGame::new_rand_with(3, &RuleSet::default(), seed, |observer| Box::new(CountingBot::new(observer)));
+
+            for _ in 0..10_000 {
+                if game.over {
+                    break;
+                }
+
+                game.step().unwrap();
+
+                for player in &game.players {
+                    let observer = player.observer().unwrap();
+
+                    for (other, estimate) in observer.other_players.iter().enumerate() {
+                        let hand = game.state.hand(observer.seat_id(other));
+
+                        for (card, &probability) in estimate.cards.cards.iter().enumerate() {
+                            match probability {
+                                Probability::Known(amount) => assert_eq!(hand[card], amount),
+                                Probability::MoreThan(amount) => assert!(hand[card] >= amount),
+                                Probability::Unknown(_) => {},
+                            }
+                        }
+                    }
+                }
+            }
+
+            assert!(game.over);
+        }
+    }
+
+    #[test]
+    fn queries_reveal_cards() {
+        let mut own_deck = vec![0; 13];
+        own_deck[..7].fill(1);
+
+        let mut observer = GameObserver::new(2, Some(own_deck), 0, &RuleSet::default());
+
+        // The asker had to hold one before receiving two, as holding two would have completed a set
+        observer.query(0, 1, 10, 2, 0).unwrap();
+
+        assert!(matches!(observer.other_players[0].cards.cards[10], Probability::Known(3)));
+        assert!(matches!(observer.other_players[1].cards.cards[10], Probability::Known(0)));
+        assert_eq!(observer.other_players[1].cards.size, 5);
+    }
+
+    #[test]
+    fn failed_asks_reveal_the_askers_cards() {
+        let mut own_deck = vec![0; 13];
+        own_deck[..7].fill(1);
+
+        let mut observer = GameObserver::new(2, Some(own_deck), 0, &RuleSet::default());
+
+        observer.observe(&Observation::Query { player: 1, target: 2, card: 10, amount: 0, books: 0 }).unwrap();
+        observer.observe(&Observation::Query { player: 2, target: 0, card: 11, amount: 0, books: 0 }).unwrap();
+
+        assert!(matches!(observer.other_players[0].cards.cards[10], Probability::MoreThan(1)));
+        assert!(matches!(observer.other_players[1].cards.cards[10], Probability::Known(0)));
+        assert!(matches!(observer.other_players[1].cards.cards[11], Probability::MoreThan(1)));
+
+        // Nobody can ask the observer for a card it holds without getting it
+        assert!(observer.observe(&Observation::Query { player: 1, target: 0, card: 0, amount: 0, books: 0 }).is_err());
+    }
+
+    #[test]
+    fn placing_after_a_pickup() {
+        let rules = RuleSet { ranks: 2, suits: 2, book_size: 2, small_game_hand: 1, ..Default::default() };
+        let mut observer = GameObserver::new(1, Some(vec![0, 1]), 0, &rules);
+
+        // Only one card is left in the stock, so the other player has to hold one of the two unseen 0s
+        observer.pickup(0).unwrap();
+
+        assert!(matches!(observer.other_players[0].cards.cards[0], Probability::MoreThan(1)));
+
+        observer.place(0, 0, 1).unwrap();
+
+        assert_eq!(observer.other_players[0].cards.size, 0);
+        assert!(matches!(observer.deck.cards[0], Probability::Known(0)));
+        assert!(matches!(observer.deck.cards[1], Probability::Known(1)));
+    }
+
+    #[test]
+    fn sampled_worlds_agree_with_the_estimates() {
+        let mut rng = util::rng_from_seed(0);
+        let mut game = BotGame::new_rand_with(4, &RuleSet::default(), 0, |observer| Box::new(CountingBot::new(observer)));
+
+        for _ in 0..10_000 {
+            if game.over {
+                break;
+            }
+
+            game.step().unwrap();
+
+            let observer = game.players[0].observer().unwrap();
+            let world = observer.sample_world(&mut rng).unwrap();
+
+            assert_eq!(world.stock.iter().sum::<usize>(), observer.deck.size);
+
+            for (hand, player) in world.hands.iter().zip(&observer.other_players) {
+                assert_eq!(hand.iter().sum::<usize>(), player.cards.size);
+
+                for (&amount, &probability) in hand.iter().zip(&player.cards.cards) {
+                    match probability {
+                        Probability::Known(known) => assert_eq!(amount, known),
+                        Probability::MoreThan(least) => assert!(amount >= least),
+                        Probability::Unknown(_) => {},
+                    }
+                }
+            }
+        }
+
+        assert!(game.over);
+    }
+
+    #[test]
+    fn worlds_on_a_small_deck() {
+        let rules = RuleSet { ranks: 2, suits: 2, book_size: 2, small_game_hand: 1, ..Default::default() };
+        let observer = GameObserver::new(1, Some(vec![0, 1]), 0, &rules);
+
+        // The other player was dealt one of the three cards the observer can't see
+        let worlds = observer.worlds(10).unwrap().unwrap();
+
+        assert_eq!(worlds.len(), 2);
+
+        for (world, probability) in worlds {
+            if world.hands[0] == [1, 0] {
+                assert_eq!(world.stock, [1, 1]);
+                assert!((probability - 2. / 3.).abs() < 1e-9);
+            } else {
+                assert_eq!(world, World { hands: vec![vec![0, 1]], stock: vec![2, 0] });
+                assert!((probability - 1. / 3.).abs() < 1e-9);
+            }
+        }
+
+        assert!(observer.worlds(1).unwrap().is_none());
+    }
+
+    #[test]
+    fn samples_follow_the_worlds() {
+        let rules = RuleSet { ranks: 3, suits: 3, book_size: 3, small_game_hand: 2, large_game_players: 4, ..Default::default() };
+        let mut observer = GameObserver::new(2, Some(vec![1, 1, 0]), 0, &rules);
+
+        // Seat 1 holds a 2 or two of them, while seat 2 holds none
+        observer.observe(&Observation::Query { player: 1, target: 2, card: 2, amount: 0, books: 0 }).unwrap();
+        observer.pickup(1).unwrap();
+
+        let worlds = observer.worlds(100).unwrap().unwrap();
+        let mut deals = observer.deals();
+        let mut rng = util::rng_from_seed(0);
+        let mut counts = vec![0; worlds.len()];
+        let samples = 20000;
+
+        assert!(worlds.len() > 2);
+
+        for _ in 0..samples {
+            let world = deals.sample(&mut rng).unwrap();
+            counts[worlds.iter().position(|(other, _)| *other == world).unwrap()] += 1;
+        }
+
+        for ((_, probability), count) in worlds.iter().zip(counts) {
+            assert!((count as f64 / samples as f64 - probability).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn drawing_agrees_with_every_deal() {
+        let rules = RuleSet { ranks: 3, suits: 4, book_size: 4, small_game_hand: 3, ..Default::default() };
+        let mut observer = GameObserver::new(1, Some(vec![1, 1, 1]), 0, &rules);
+
+        observer.observe(&Observation::Query { player: 1, target: 0, card: 0, amount: 1, books: 0 }).unwrap();
+        observer.observe(&Observation::Pickup { player: 1, card: None }).unwrap();
+
+        // Go through every hand the other player could have been dealt from the unseen cards and every card they could
+        // have drawn, keeping those where they asked for a 0 and didn't place any sets
+        let unseen = [0, 0, 0, 1, 1, 1, 2, 2, 2];
+        let mut expected = vec![0.; 5];
+
+        for a in 0..unseen.len() {
+            for b in a + 1..unseen.len() {
+                for c in b + 1..unseen.len() {
+                    let held = [a, b, c].iter().filter(|&&card| unseen[card] == 0).count();
+
+                    for top in (0..unseen.len()).filter(|top| ![a, b, c].contains(top)) {
+                        let after = held + 1 + (unseen[top] == 0) as usize;
+
+                        if held > 0 && after < rules.book_size {
+                            expected[after] += 1.;
+                        }
+                    }
+                }
+            }
+        }
+
+        let total = expected.iter().sum::<f64>();
+        let mut distribution = observer.beliefs.distribution(0, 0);
+        distribution.resize(expected.len(), 0.);
+
+        for (probability, expected) in distribution.into_iter().zip(expected) {
+            assert!((probability - expected / total).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use fish_core::{game::Bot, neural_network::{Activation, NeuralNetwork}, rules::RuleSet, util};
+
+/// Random networks for `count` bots in games of `players` players, which are the same on every call
+pub fn networks(count: usize, players: usize, rules: &RuleSet) -> Vec<NeuralNetwork> {
+    let mut rng = util::rng_from_seed(0);
+
+    (0..count).map(|_| NeuralNetwork::new_rand(
+        Bot::inputs(players, rules),
+        &[(Bot::outputs(players, rules), Activation::Softmax)],
+        &mut rng
+    )).collect()
+}
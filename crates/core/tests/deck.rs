@@ -1,18 +1,19 @@
 #[cfg(test)]
 mod tests {
-    use fish_core::observer::Deck;
+    use fish_core::{observer::Deck, rules::RuleSet};
 
     #[test]
     fn new_full() {
-        let deck = Deck::new_full();
+        let deck = Deck::new_full(&RuleSet::default());
 
         assert_eq!(deck.size as f32, deck.total());
     }
 
     #[test]
     fn new_empty() {
-        let deck = Deck::new_empty();
+        let deck = Deck::new_empty(RuleSet::default().ranks);
 
-        
+        assert_eq!(deck.size, 0);
+        assert_eq!(deck.total(), 0.);
     }
 }
\ No newline at end of file
@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod tests {
+    use fish_core::{baseline::CountingBot, error::GoFishError, endgame::{Endgame, EndgameBot, EndgameSolver}, game::{BotGame, Query}, player::PlayerView, rules::RuleSet};
+
+    fn game_with_empty_deck(players: usize, seed: u64) -> BotGame {
+        let mut game = BotGame::new_rand_with(players, &RuleSet::default(), seed, |observer| Box::new(CountingBot::new(observer)));
+
+        for _ in 0..10_000 {
+            if game.state.stock_size() == 0 || game.over {
+                break;
+            }
+
+            game.step().unwrap();
+        }
+
+        assert!(game.state.stock_size() == 0 || game.over);
+
+        game
+    }
+
+    #[test]
+    fn known_endgames_are_solved() {
+        let rules = RuleSet::default();
+        let ranks = |amounts: &[usize]| {
+            let mut hand = vec![0; rules.ranks];
+            hand[..amounts.len()].copy_from_slice(amounts);
+            hand
+        };
+
+        // Seat 0 can take both ranks one after another
+        let position = Endgame { hands: vec![ranks(&[1, 2]), ranks(&[3, 2])], books: vec![5, 6], current_player: 0, passes: 0 };
+        let mut solver = EndgameSolver::new(&rules);
+
+        assert_eq!(solver.solve(&position).unwrap(), vec![7, 6]);
+
+        // Without another turn after a successful ask, seat 1 takes the other rank back first
+        let rules = RuleSet { continue_on_success: false, ..rules };
+        let mut solver = EndgameSolver::new(&rules);
+
+        assert_eq!(solver.solve(&position).unwrap(), vec![6, 7]);
+        assert_eq!(position.play(Query { player: 1, card: 0 }, &rules).current_player, 1);
+    }
+
+    #[test]
+    fn failed_asks_are_searched() {
+        let rules = RuleSet { ranks: 4, continue_on_success: false, ..RuleSet::default() };
+        let position = Endgame {
+            hands: vec![vec![0, 1, 1, 1], vec![1, 3, 3, 1], vec![3, 0, 0, 2]],
+            books: vec![0, 0, 0],
+            current_player: 0,
+            passes: 0,
+        };
+        let mut solver = EndgameSolver::new(&rules);
+
+        // Seat 0 finishes ahead of seat 2 only by passing the turn. Successful asks come first, so a failed one is only
+        // picked when it is strictly better.
+        let (query, books) = solver.best_query(&position).unwrap();
+
+        assert_eq!(position.hands[query.player][query.card], 0);
+        assert_eq!(books, vec![1, 3, 0]);
+        assert_eq!(position.play(query, &rules).passes, 1);
+    }
+
+    #[test]
+    fn large_endgames_are_given_up() {
+        let rules = RuleSet::default();
+
+        // A 4 player endgame with few cards left is solved within the limit
+        let game = game_with_empty_deck(4, 0);
+        let position = Endgame::from_state(&game.state, game.current_player, &rules);
+        assert!(position.hands.iter().flatten().sum::<usize>() >= 12);
+
+        let books = EndgameSolver::new(&rules).solve(&position).unwrap();
+        assert_eq!(books.iter().sum::<usize>(), rules.books());
+
+        // With 32 cards in 4 hands there are far too many positions
+        let position = Endgame {
+            hands: vec![
+                vec![0, 0, 0, 1, 0, 1, 2, 1, 1, 0, 3, 0, 0],
+                vec![0, 0, 0, 3, 2, 2, 0, 1, 1, 0, 0, 0, 0],
+                vec![3, 0, 0, 0, 1, 0, 1, 1, 1, 0, 1, 0, 0],
+                vec![1, 0, 0, 0, 1, 1, 1, 1, 1, 0, 0, 0, 0],
+            ],
+            books: vec![2, 1, 1, 1],
+            current_player: 0,
+            passes: 0,
+        };
+        let mut solver = EndgameSolver::new(&rules);
+        solver.max_positions = 1000;
+
+        assert!(matches!(solver.solve(&position), Err(GoFishError::SearchLimit(1000))));
+        assert!(matches!(solver.best_query(&position), Err(GoFishError::SearchLimit(1000))));
+    }
+
+    #[test]
+    fn analysis_agrees_with_the_actual_cards() {
+        for seed in 0..5 {
+            let game = game_with_empty_deck(2, seed);
+
+            if game.over {
+                continue;
+            }
+
+            let mut solver = EndgameSolver::new(&game.rules);
+            let truth = solver.solve(&Endgame::from_state(&game.state, game.current_player, &game.rules)).unwrap();
+            assert_eq!(truth.iter().sum::<usize>(), game.rules.books());
+
+            let view = PlayerView::new(game.current_player, &game.state, &game.rules);
+            let observer = game.players[game.current_player].observer().unwrap();
+            let analysis = solver.analyze(observer, &view.books, game.current_player, 100_000).unwrap().unwrap();
+
+            assert!(analysis.worlds >= 1);
+            assert!((analysis.value.iter().sum::<f32>() - 1.).abs() < 1e-4);
+            assert!((analysis.books.iter().sum::<f32>() - game.rules.books() as f32).abs() < 1e-3);
+            assert!(view.is_legal(&analysis.best_move().unwrap()));
+
+            // Someone who can't see the hands can't do better than someone who can
+            if analysis.worlds == 1 {
+                assert_eq!(analysis.books, truth.iter().map(|&books| books as f32).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    fn endgame_bots_finish_games() {
+        // Seed 1 leaves an endgame of 4 players with few enough deals but too many positions to solve, so the bot has
+        // to leave the move to the other player
+        for (players, seed, max_worlds) in (0..5).map(|seed| (3, seed, 100)).chain([(4, 1, 100_000)]) {
+            let mut game = BotGame::new_rand_with(players, &RuleSet::default(), seed, |observer| match observer.id {
+                0 => Box::new(EndgameBot::new(Box::new(CountingBot::new(observer.clone())), observer, max_worlds)),
+                _ => Box::new(CountingBot::new(observer)),
+            });
+
+            for _ in 0..1000 {
+                if game.over {
+                    break;
+                }
+
+                game.step().unwrap();
+            }
+
+            assert!(game.over);
+            assert_eq!(game.state.total_books(&game.rules), game.rules.books());
+        }
+    }
+}
@@ -0,0 +1,300 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use fish_core::{card::Card, error::GoFishError, event::GameEvent, game::{Bot, BotGame, IllegalMove, Move, Query}, neural_network::NeuralNetwork, player::{GoFishPlayer, PlayerView}, result::GameResult, rules::{RuleSet, EmptyHandRule}};
+    use crate::common;
+
+    fn networks(players: usize) -> Vec<NeuralNetwork> {
+        common::networks(players, players, &RuleSet::default())
+    }
+
+    #[test]
+    fn placed_sets_never_exceed_deck() {
+        for seed in 0..20 {
+            let mut game = BotGame::new_rand(&networks(4), &RuleSet::default(), seed);
+
+            for _ in 0..1000 {
+                game.step().unwrap();
+                if game.over {
+                    break;
+                }
+            }
+
+            assert!(game.state.total_books(&game.rules) <= game.rules.books());
+        }
+    }
+
+    #[test]
+    fn two_player_games_place_every_set() {
+        for seed in 0..20 {
+            let mut game = BotGame::new_rand(&networks(2), &RuleSet::default(), seed);
+
+            for _ in 0..10_000 {
+                if game.over {
+                    break;
+                }
+
+                game.step().unwrap();
+            }
+
+            assert!(game.over);
+
+            let result = game.result().unwrap();
+            assert_eq!(result.books.iter().sum::<usize>(), game.rules.books());
+            assert_eq!(game.state.stock_size(), 0);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_game() {
+        let play = |seed| {
+            let mut game = BotGame::new_rand(&networks(3), &RuleSet::default(), seed);
+
+            for _ in 0..1000 {
+                game.step().unwrap();
+            }
+
+            game.events
+        };
+
+        assert_eq!(play(7), play(7));
+        assert_ne!(play(7), play(8));
+    }
+
+    #[test]
+    fn pairs_and_refilling() {
+        let rules = RuleSet { book_size: 2, empty_hand: EmptyHandRule::Refill, ..Default::default() };
+
+        for seed in 0..20 {
+            let mut game = BotGame::new_rand(&networks(2), &rules, seed);
+
+            for _ in 0..10_000 {
+                if game.over {
+                    break;
+                }
+
+                game.step().unwrap();
+
+                game.validate().unwrap();
+            }
+
+            assert!(game.over);
+
+            assert_eq!(game.result().unwrap().books.iter().sum::<usize>(), rules.books());
+        }
+    }
+
+    #[test]
+    fn dealt_sets_are_placed() {
+        let rules = RuleSet { book_size: 2, ..Default::default() };
+        let game = BotGame::new_rand(&networks(4), &rules, 0);
+
+        // With pairs as sets, this deal starts with at least one set on the table
+        assert!(game.state.total_books(&rules) > 0);
+        assert!(game.events.iter().any(|event| matches!(event, GameEvent::Place { .. })));
+        game.validate().unwrap();
+    }
+
+    #[test]
+    fn sets_have_to_fit_the_deck() {
+        for book_size in [0, 3] {
+            let rules = RuleSet { book_size, ..Default::default() };
+
+            assert!(matches!(rules.validate(), Err(GoFishError::InvalidState(_))));
+            assert!(BotGame::from_deal(vec![Vec::new(); 2], Vec::new(), &rules, |_| Box::new(FirstLegalMove)).is_err());
+        }
+
+        RuleSet { suits: 8, book_size: 2, ..Default::default() }.validate().unwrap();
+    }
+
+    #[derive(Clone)]
+    struct FirstLegalMove;
+
+    impl GoFishPlayer for FirstLegalMove {
+        fn choose_move(&mut self, view: &PlayerView) -> Result<Option<Move>, GoFishError> {
+            Ok(view.legal_moves().first().copied())
+        }
+    }
+
+    #[derive(Clone)]
+    struct Passing;
+
+    impl GoFishPlayer for Passing {
+        fn choose_move(&mut self, _view: &PlayerView) -> Result<Option<Move>, GoFishError> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn passing_skips_players_who_cant_move() {
+        let rules = RuleSet { ranks: 3, small_game_hand: 4, empty_hand: EmptyHandRule::SitOut, ..Default::default() };
+        let cards = |rank: usize, suits: &[usize]| suits.iter().map(|&suit| Card::new(rank, suit)).collect::<Vec<_>>();
+        let hands = vec![[cards(0, &[0, 1]), cards(2, &[0, 1])].concat(), cards(1, &[0, 1, 2, 3])];
+        let stock = [cards(0, &[2, 3]), cards(2, &[2, 3])].concat();
+
+        assert!(BotGame::from_deal(vec![hands[0].clone()], stock.clone(), &rules, |_| Box::new(Passing)).is_err());
+
+        // Seat 1 places the set they were dealt and sits out, so the turn comes straight back to seat 0
+        let mut game = BotGame::from_deal(hands, stock, &rules, |_| Box::new(Passing)).unwrap();
+        game.step().unwrap();
+
+        assert_eq!(game.current_player, 0);
+        assert!(!game.over);
+    }
+
+    #[test]
+    fn mixed_players() {
+        let networks = networks(3);
+
+        let mut game = BotGame::new_rand_with(3, &RuleSet::default(), 0, |observer| match observer.id {
+            1 => Box::new(FirstLegalMove),
+            id => Box::new(Bot::new(observer, &networks[id])),
+        });
+
+        for _ in 0..1000 {
+            game.step().unwrap();
+            if game.over {
+                break;
+            }
+        }
+
+        assert!(game.players[1].observer().is_none());
+        assert!(game.state.total_books(&game.rules) <= game.rules.books());
+    }
+
+    #[test]
+    fn small_deck() {
+        let rules = RuleSet { ranks: 6, ..RuleSet::default() };
+
+        for seed in 0..20 {
+            let mut game = BotGame::new_rand(&common::networks(2, 2, &rules), &rules, seed);
+
+            for _ in 0..10_000 {
+                if game.over {
+                    break;
+                }
+
+                game.step().unwrap();
+            }
+
+            assert!(game.over);
+
+            assert_eq!(game.state.total_books(&game.rules), 6);
+        }
+    }
+
+    #[test]
+    fn double_deck() {
+        let rules = RuleSet { suits: 8, ..RuleSet::default() };
+        let mut game = BotGame::new_rand(&common::networks(9, 9, &rules), &rules, 0);
+
+        assert_eq!(game.rules.books(), 26);
+
+        for _ in 0..1000 {
+            game.step().unwrap();
+            game.validate().unwrap();
+            if game.over {
+                break;
+            }
+        }
+
+        assert_eq!(game.state.placed_counts().len(), 13);
+        assert!(game.state.total_books(&game.rules) <= 26);
+    }
+
+    #[test]
+    fn restoring_a_snapshot_replays_the_same_game() {
+        let mut game = BotGame::new_rand(&networks(2), &RuleSet::default(), 3);
+
+        for _ in 0..10 {
+            game.step().unwrap();
+        }
+
+        let snapshot = game.snapshot();
+        let observer = serde_json::to_value(game.players[0].observer()).unwrap();
+        let mut clone = game.clone();
+
+        for _ in 0..10_000 {
+            if game.over {
+                break;
+            }
+
+            game.step().unwrap();
+        }
+
+        assert!(game.over);
+
+        let events = game.events.clone();
+        game.restore(snapshot);
+
+        assert!(!game.over);
+        assert_eq!(serde_json::to_value(game.players[0].observer()).unwrap(), observer);
+        game.validate().unwrap();
+
+        for _ in 0..10_000 {
+            if game.over {
+                break;
+            }
+
+            game.step().unwrap();
+            clone.step().unwrap();
+        }
+
+        assert!(game.over);
+
+        assert_eq!(game.events, events);
+        assert_eq!(clone.events, events);
+    }
+
+    #[test]
+    fn apply_move_checks_legality() {
+        let mut game = BotGame::new_rand(&networks(3), &RuleSet::default(), 0);
+        let hand = game.state.hand(0);
+        let held = hand.iter().position(|&amount| amount > 0).unwrap();
+        let missing = hand.iter().position(|&amount| amount == 0).unwrap();
+        let illegal = |result| match result {
+            Err(GoFishError::IllegalMove(illegal_move)) => Some(illegal_move),
+            _ => None,
+        };
+
+        assert_eq!(illegal(game.apply_move(1, Move::Query(Query { player: 0, card: held }))), Some(IllegalMove::NotYourTurn { seat: 1, current_player: 0 }));
+        assert_eq!(illegal(game.apply_move(0, Move::Pickup)), Some(IllegalMove::CannotPickUp));
+        assert_eq!(illegal(game.apply_move(0, Move::Query(Query { player: 0, card: held }))), Some(IllegalMove::InvalidTarget(0)));
+        assert_eq!(illegal(game.apply_move(0, Move::Query(Query { player: 3, card: held }))), Some(IllegalMove::InvalidTarget(3)));
+        assert_eq!(illegal(game.apply_move(0, Move::Query(Query { player: 1, card: missing }))), Some(IllegalMove::RankNotHeld(missing)));
+        assert!(game.events.iter().all(|event| matches!(event, GameEvent::Deal { .. } | GameEvent::Place { .. })));
+
+        let given = game.state.hand(1)[held];
+        let outcome = game.apply_move(0, Move::Query(Query { player: 1, card: held })).unwrap();
+
+        assert_eq!(outcome.received.len(), given);
+        assert_eq!(outcome.drawn.len(), (given == 0) as usize);
+        assert_eq!(game.current_player == 0, outcome.another_turn);
+    }
+
+    #[test]
+    fn wrong_network_size_is_an_error() {
+        let mut game = BotGame::new_rand(&networks(2), &RuleSet::default(), 0);
+        let expected = Bot::inputs(3, &game.rules);
+
+        let mut bot = Bot::new(game.players[0].observer().unwrap().clone(), &networks(3)[0]);
+        let actual = Bot::inputs(2, &game.rules);
+
+        assert!(matches!(bot.eval(), Err(GoFishError::ShapeMismatch { expected: e, actual: a }) if e == expected && a == actual));
+
+        game.players[0] = Box::new(bot);
+        assert!(game.step().is_err());
+    }
+
+    #[test]
+    fn result_ranks_ties() {
+        let result = GameResult::from_books(vec![4, 2, 4, 3]);
+
+        assert_eq!(result.positions, vec![0, 3, 0, 2]);
+        assert_eq!(result.winners(), vec![0, 2]);
+        assert_eq!(result.winner(), None);
+        assert!(result.is_tie());
+        assert_eq!(result.ranking(), vec![0, 2, 3, 1]);
+    }
+}
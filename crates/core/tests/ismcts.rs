@@ -0,0 +1,54 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use fish_core::{game::{Bot, BotGame}, ismcts::IsmctsBot, player::PlayerView, rules::RuleSet};
+    use crate::common::networks;
+
+    fn game(rules: &RuleSet, seed: u64) -> BotGame {
+        let network = networks(1, 2, rules).remove(0);
+
+        BotGame::new_rand_with(2, rules, seed, |observer| match observer.id {
+            0 => Box::new(IsmctsBot::new(observer, 30, seed)),
+            _ => Box::new(Bot::new(observer, &network)),
+        })
+    }
+
+    #[test]
+    fn plays_whole_games() {
+        let rules = RuleSet { ranks: 6, ..RuleSet::default() };
+
+        for seed in 0..3 {
+            let mut game = game(&rules, seed);
+
+            for _ in 0..10_000 {
+                if game.over {
+                    break;
+                }
+
+                game.step().unwrap();
+            }
+
+            assert!(game.over);
+
+            assert_eq!(game.state.total_books(&rules), rules.books());
+        }
+    }
+
+    #[test]
+    fn search_only_tries_legal_moves() {
+        let rules = RuleSet::default();
+        let game = game(&rules, 1);
+        let view = PlayerView::new(0, &game.state, &rules);
+
+        let mut bot = IsmctsBot::new(game.players[0].observer().unwrap().clone(), 200, 0);
+        let visits = bot.search(&view).unwrap();
+
+        assert_eq!(visits.iter().map(|&(m, _)| m).collect::<Vec<_>>(), view.legal_moves());
+        assert_eq!(visits.iter().map(|&(_, visits)| visits).sum::<u32>(), 200);
+
+        // The same seed searches the same way
+        let mut again = IsmctsBot::new(game.players[0].observer().unwrap().clone(), 200, 0);
+        assert_eq!(again.search(&view).unwrap(), visits);
+    }
+}
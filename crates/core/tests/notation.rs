@@ -0,0 +1,107 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use fish_core::{card::Card, error::GoFishError, game::{Bot, BotGame}, notation, rules::{RuleSet, EmptyHandRule}};
+    use crate::common::networks;
+
+    fn played(players: usize, rules: &RuleSet, seed: u64) -> BotGame {
+        let mut game = BotGame::new_rand(&networks(players, players, rules), rules, seed);
+
+        for _ in 0..1000 {
+            game.step().unwrap();
+            if game.over {
+                break;
+            }
+        }
+
+        game
+    }
+
+    #[test]
+    fn round_trip() {
+        let pairs = RuleSet { ranks: 6, book_size: 2, empty_hand: EmptyHandRule::Refill, ..Default::default() };
+
+        for (players, rules) in [(2, RuleSet::default()), (3, RuleSet::default()), (2, pairs)] {
+            for seed in 0..5 {
+                let game = played(players, &rules, seed);
+                let text = notation::write(&game.replay());
+                let replay = notation::parse(&text).unwrap();
+
+                assert_eq!(replay.events, game.events, "{text}");
+                assert_eq!(replay.rules, rules);
+                assert_eq!(notation::write(&replay), text);
+            }
+        }
+    }
+
+    #[test]
+    fn pickups_in_a_row_round_trip() {
+        // Every card completes a set, so a player who picks up has to pick up again until the deck is empty
+        let rules = RuleSet { ranks: 6, book_size: 1, ..Default::default() };
+        let game = played(2, &rules, 0);
+        let text = notation::write(&game.replay());
+
+        assert!(text.lines().any(|line| line.contains(" draws ") && line.split_whitespace().count() > 3), "{text}");
+        assert_eq!(notation::parse(&text).unwrap().events, game.events, "{text}");
+    }
+
+    #[test]
+    fn unfinished_games_can_be_continued() {
+        let rules = RuleSet::default();
+        let networks = networks(2, 2, &rules);
+        let mut game = BotGame::new_rand(&networks, &rules, 1);
+
+        for _ in 0..10 {
+            game.step().unwrap();
+        }
+
+        let text = notation::write(&game.replay());
+        let mut parsed = notation::parse_game(&text, |observer| {
+            let network = &networks[observer.id];
+            Box::new(Bot::new(observer, network))
+        }).unwrap();
+
+        assert_eq!(parsed.events, game.events);
+
+        for _ in 0..10_000 {
+            if parsed.over {
+                break;
+            }
+
+            parsed.step().unwrap();
+        }
+
+        assert!(parsed.over);
+
+        parsed.validate().unwrap();
+    }
+
+    #[test]
+    fn wrong_records_are_errors() {
+        let text = notation::write(&played(2, &RuleSet::default(), 0).replay());
+        let lines = text.lines().collect::<Vec<_>>();
+        let (line, ask) = lines.iter().enumerate().find(|(_, line)| line.contains(": got ")).unwrap();
+
+        let wrong = text.replacen(ask, &ask.replace(": got ", ": got 1"), 1);
+        assert!(matches!(notation::parse(&wrong), Err(GoFishError::Notation { line: l, .. }) if l == line + 1));
+
+        let missing_deal = lines[1..].join("\n");
+        assert!(matches!(notation::parse(&missing_deal), Err(GoFishError::Notation { line: 1, .. })));
+
+        assert!(notation::parse("P0 asks P1 for 7: got lots").is_err());
+    }
+
+    #[test]
+    fn cards_round_trip() {
+        let rules = RuleSet { suits: 8, ..Default::default() };
+
+        for card in Card::full_deck(&rules) {
+            assert_eq!(card.to_string().parse::<Card>(), Ok(card));
+        }
+
+        assert_eq!(Card::new(12, 6).to_string(), "12H2");
+        assert!("7X".parse::<Card>().is_err());
+        assert!("7H1".parse::<Card>().is_err());
+    }
+}
@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use fish_core::{baseline::{self, CountingBot}, endgame::{Endgame, EndgameSolver}, error::GoFishError, game::{BotGame, Move}, oracle::{self, OracleBot}, player::{GoFishPlayer, PlayerView}, rating::Ratings, rules::RuleSet, tournament::Tournament};
+
+    #[test]
+    fn oracle_beats_the_baselines() {
+        let mut entrants = baseline::entrants();
+        entrants.push(oracle::entrant());
+
+        let mut tournament = Tournament::new(RuleSet::default(), 2, 5, 1000, 0);
+        tournament.oracle_agreement = true;
+
+        let result = tournament.run(&entrants, &mut Ratings::default()).unwrap();
+        let oracle = entrants.len() - 1;
+
+        assert!((0..oracle).all(|i| result.head_to_head[oracle][i].win_rate() > 0.5));
+        assert_eq!(result.agreement[oracle].rate(), 1.);
+        assert!((0..oracle).all(|i| result.agreement[i].agreed <= result.agreement[i].decisions));
+        assert!(result.to_string().contains("Oracle"));
+    }
+
+    #[test]
+    fn oracle_asks_for_cards_it_can_get() {
+        let rules = RuleSet::default();
+
+        for seed in 0..10 {
+            let game = BotGame::new_rand_with(3, &rules, seed, |observer| Box::new(OracleBot::new(&observer.rules)));
+            let mut view = PlayerView::new(0, &game.state, &rules);
+
+            let mut oracle = OracleBot::new(&rules);
+            assert!(oracle.choose_move(&view).is_err());
+
+            view.truth = Some(&game.state);
+            let Some(Move::Query(query)) = oracle.choose_move(&view).unwrap() else {
+                panic!("The oracle should ask");
+            };
+
+            let holders = (1..3).filter(|&seat| game.state.hand(seat).iter().zip(&view.hand).any(|(&theirs, &mine)| theirs > 0 && mine > 0)).count();
+            assert_eq!(game.state.hand(query.player)[query.card] > 0, holders > 0);
+        }
+    }
+
+    #[test]
+    fn oracle_solves_small_endgames() {
+        let rules = RuleSet::default();
+
+        // The endgame of 4 players with seed 1 is too large to solve
+        for (players, seed) in (0..5).map(|seed| (3, seed)).chain([(4, 1)]) {
+            let mut game = BotGame::new_rand_with(players, &rules, seed, |observer| Box::new(CountingBot::new(observer)));
+
+            for _ in 0..10_000 {
+                if game.state.stock_size() == 0 || game.over {
+                    break;
+                }
+
+                game.step().unwrap();
+            }
+
+            assert!(game.state.stock_size() == 0 || game.over);
+
+            if game.over {
+                continue;
+            }
+
+            let mut view = PlayerView::new(game.current_player, &game.state, &rules);
+            view.truth = Some(&game.state);
+            let chosen = OracleBot::new(&rules).choose_move(&view).unwrap();
+
+            let mut solver = EndgameSolver::new(&rules);
+            solver.max_positions = oracle::MAX_POSITIONS;
+
+            match solver.best_query(&Endgame::from_state(&game.state, game.current_player, &rules)) {
+                Ok((query, _)) => assert_eq!(chosen, Some(Move::Query(query))),
+                Err(GoFishError::SearchLimit(_)) => assert!(players == 4 && view.is_legal(&chosen.unwrap())),
+                Err(err) => panic!("{err}"),
+            }
+        }
+    }
+
+    #[test]
+    fn agreement_is_measured_in_games_of_four() {
+        let mut game = BotGame::new_rand_with(4, &RuleSet::default(), 1, |observer| Box::new(CountingBot::new(observer)));
+        let agreement = oracle::play_measuring_agreement(&mut game, 1000).unwrap();
+
+        assert!(game.over);
+        assert!(agreement.iter().all(|seat| seat.agreed <= seat.decisions));
+    }
+}
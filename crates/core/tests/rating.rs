@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    use fish_core::{rating::{self, Rating, Ratings, INITIAL_RATING}, result::GameResult};
+
+    #[test]
+    fn multiplayer_elo() {
+        let mut ratings = vec![Rating::default(); 4];
+        rating::rate(&mut ratings, &GameResult::from_books(vec![4, 2, 4, 3]), 32.);
+
+        // Ratings are only passed around between the players
+        assert!((ratings.iter().map(|rating| rating.elo).sum::<f32>() - 4. * INITIAL_RATING).abs() < 1e-3);
+        assert_eq!(ratings[0], ratings[2]);
+        assert!(ratings[0].elo > ratings[3].elo && ratings[3].elo > ratings[1].elo);
+        assert!(ratings.iter().all(|rating| rating.games == 1));
+
+        // Beating a much stronger player is worth more than beating an equal one
+        let strong = Rating { elo: 1900., games: 10 };
+        let upset = rating::rating_changes(&[Rating::default(), strong], &GameResult::from_books(vec![7, 6]), 32.);
+        let even = rating::rating_changes(&[Rating::default(), Rating::default()], &GameResult::from_books(vec![7, 6]), 32.);
+        assert!(upset[0] > even[0]);
+    }
+
+    #[test]
+    fn named_ratings_are_kept() {
+        let mut ratings = Ratings::default();
+        let changes = ratings.update(&["alice", "bot"], &GameResult::from_books(vec![8, 5]));
+
+        assert_eq!(changes, vec![16., -16.]);
+        assert_eq!(ratings.leaderboard().iter().map(|(name, _)| *name).collect::<Vec<_>>(), vec!["alice", "bot"]);
+        assert_eq!(ratings.get("nobody"), Rating::default());
+
+        let path = std::env::temp_dir().join("fish_core_ratings_test.json");
+        ratings.save(&path).unwrap();
+        let loaded = Ratings::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, ratings);
+        assert_eq!(Ratings::load(&path).unwrap(), Ratings::default());
+    }
+}
@@ -0,0 +1,34 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use fish_core::{event::GameEvent, game::BotGame, replay::Replay, rules::RuleSet};
+    use crate::common::networks;
+
+    #[test]
+    fn replay_reconstructs_observers() {
+        let rules = RuleSet::default();
+        let networks = networks(3, 3, &rules);
+
+        let mut game = BotGame::new_rand(&networks, &rules, 0);
+
+        for _ in 0..1000 {
+            game.step().unwrap();
+            if game.over {
+                break;
+            }
+        }
+
+        let path = std::env::temp_dir().join("fish_core_replay_test.json");
+        game.replay().save(&path).unwrap();
+        let replay = Replay::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(replay.events, game.events);
+        assert!(matches!(replay.events.first(), Some(GameEvent::Deal { seat: 0, .. })));
+
+        for (observer, player) in replay.observers().unwrap().iter().zip(game.players.iter()) {
+            assert_eq!(serde_json::to_value(observer).unwrap(), serde_json::to_value(player.observer().unwrap()).unwrap());
+        }
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use fish_core::{card::Card, error::GoFishError, rules::RuleSet, state::GameState};
+
+    fn dealt() -> GameState {
+        let mut stock = Card::full_deck(&RuleSet::default());
+        let hands = vec![
+            (0..7).map(|rank| Card::new(rank, 0)).collect(),
+            (6..13).map(|rank| Card::new(rank, 1)).collect(),
+        ];
+
+        stock.retain(|card| !hands.iter().any(|hand: &Vec<Card>| hand.contains(card)));
+
+        GameState::new(hands, stock, 13)
+    }
+
+    #[test]
+    fn conservation() {
+        let rules = RuleSet::default();
+        let mut state = dealt();
+
+        assert!(state.validate(&rules).is_ok());
+
+        state.stock.pop();
+        assert!(state.validate(&rules).is_err());
+
+        state.stock.push(state.hands[0][0]);
+        assert!(state.validate(&rules).is_err());
+    }
+
+    #[test]
+    fn ranks_have_to_match_the_rules() {
+        let mut state = dealt();
+        state.ranks = 6;
+
+        assert!(matches!(state.validate(&RuleSet::default()), Err(GoFishError::InvalidState(_))));
+    }
+
+    #[test]
+    fn completed_sets_are_placed() {
+        let rules = RuleSet::default();
+        let mut state = dealt();
+
+        // Seat 1 gives its card to seat 0 and seat 0 picks up the rest
+        let cards = state.take_all(1, 6);
+        assert!(state.add_to_hand(0, &cards, &rules).is_empty());
+
+        let rest = [Card::new(6, 2), Card::new(6, 3)];
+        state.stock.retain(|card| !rest.contains(card));
+        assert_eq!(state.add_to_hand(0, &rest, &rules).len(), rules.suits);
+
+        assert_eq!(state.hand(0)[6], 0);
+        assert_eq!(state.books(&rules), vec![1, 0]);
+        assert!(state.validate(&rules).is_ok());
+
+        // Every card is still there, but seat 1 holds the set without placing it
+        let set = std::mem::take(&mut state.placed[0]);
+        state.hands[1].extend(set);
+        assert!(state.validate(&rules).is_err());
+    }
+}
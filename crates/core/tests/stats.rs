@@ -0,0 +1,61 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use fish_core::{event::GameEvent, game::BotGame, rules::RuleSet};
+    use crate::common::networks;
+
+    fn finished_game(seed: u64) -> BotGame {
+        let rules = RuleSet::default();
+        let networks = networks(2, 2, &rules);
+
+        let mut game = BotGame::new_rand(&networks, &rules, seed);
+
+        for _ in 0..10_000 {
+            if game.over {
+                break;
+            }
+
+            game.step().unwrap();
+        }
+
+        assert!(game.over);
+
+        game
+    }
+
+    #[test]
+    fn stats_add_up() {
+        let game = finished_game(0);
+        let stats = game.stats();
+        let total = stats.total();
+
+        let draws = game.events.iter().filter(|event| matches!(event, GameEvent::Draw { .. })).count();
+        let asks = game.events.iter().filter(|event| matches!(event, GameEvent::Ask { .. })).count();
+
+        assert_eq!(stats.games, 1);
+        assert_eq!(total.asks, asks);
+        assert!(total.successful_asks <= total.asks);
+        assert_eq!(total.go_fish_draws + total.pickups, draws);
+        assert_eq!(total.cards_gained, total.cards_lost);
+        assert_eq!(total.books, game.rules.books());
+        assert_eq!(stats.book_turns.len(), game.rules.books());
+        assert!(stats.book_turns.iter().all(|&turn| turn <= stats.turns));
+        assert_eq!(stats.seats.iter().map(|seat| seat.books).collect::<Vec<_>>(), game.standings().books);
+        assert_eq!(stats, game.replay().stats());
+    }
+
+    #[test]
+    fn merging_games() {
+        let first = finished_game(0).stats();
+        let second = finished_game(1).stats();
+
+        let mut merged = first.clone();
+        merged.merge(&second);
+
+        assert_eq!(merged.games, 2);
+        assert_eq!(merged.turns, first.turns + second.turns);
+        assert_eq!(merged.total().asks, first.total().asks + second.total().asks);
+        assert_eq!(merged.average_turns(), (first.turns + second.turns) as f32 / 2.);
+    }
+}
@@ -0,0 +1,52 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use fish_core::{rating::Ratings, rules::RuleSet, tournament::{self, Record, Tournament}};
+    use crate::common::networks;
+
+    #[test]
+    fn every_pair_plays_every_deal_from_every_seat() {
+        let networks = networks(3, 2, &RuleSet::default());
+        let entrants = networks.iter().enumerate().map(|(i, network)| (format!("bot{i}"), tournament::bot_entrant(network))).collect::<Vec<_>>();
+        let tournament = Tournament::new(RuleSet::default(), 2, 3, 1000, 0);
+
+        let mut ratings = Ratings::default();
+        let result = tournament.run(&entrants, &mut ratings).unwrap();
+
+        for i in 0..3 {
+            // Two opponents, three deals, two seats
+            assert_eq!(result.overall[i].games, 12);
+
+            for j in (0..3).filter(|&j| j != i) {
+                let (record, other) = (result.head_to_head[i][j], result.head_to_head[j][i]);
+
+                assert_eq!(record.games, 6);
+                assert_eq!(record.score + other.score, 6.);
+            }
+        }
+
+        assert_eq!(result.overall.iter().map(|record| record.score).sum::<f32>(), 18.);
+        assert_eq!(result, tournament.run(&entrants, &mut Ratings::default()).unwrap());
+
+        // The best entrant is rated highest
+        let best = (0..3).max_by(|&a, &b| result.overall[a].score.total_cmp(&result.overall[b].score)).unwrap();
+        assert_eq!(ratings.leaderboard()[0].0, result.names[best]);
+        assert!(ratings.players.values().all(|rating| rating.games == 12));
+        assert_eq!(result.to_string().lines().count(), 4);
+    }
+
+    #[test]
+    fn confidence_intervals_contain_the_win_rate() {
+        for (games, score) in [(10, 0.), (10, 5.), (10, 10.), (1000, 700.)] {
+            let record = Record { games, score };
+            let (low, high) = record.confidence_interval();
+
+            assert!(low <= record.win_rate() && record.win_rate() <= high);
+        }
+
+        let narrow = Record { games: 1000, score: 500. }.confidence_interval();
+        let wide = Record { games: 10, score: 5. }.confidence_interval();
+        assert!(narrow.1 - narrow.0 < wide.1 - wide.0);
+    }
+}
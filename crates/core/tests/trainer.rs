@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use fish_core::{error::GoFishError, game::Bot, neural_network::NeuralNetwork, rules::RuleSet, trainer::BotTrainer};
+
+    #[test]
+    fn same_seed_same_population() {
+        let train = |seed| {
+            let mut trainer = BotTrainer::new(8, 0.5, 2, 2, 200, RuleSet::default(), seed);
+
+            for i in 0..2 {
+                trainer.step(seed + i).unwrap();
+            }
+
+            // The networks are compared by their weights and biases, which is what they are saved as
+            trainer.players.iter().map(|agent| (agent.fitness, serde_json::to_string(&agent.network).unwrap())).collect::<Vec<_>>()
+        };
+
+        assert_eq!(train(3), train(3));
+    }
+
+    #[test]
+    fn saved_networks_evaluate_the_same() {
+        let rules = RuleSet::default();
+        let mut network = BotTrainer::new(2, 0.5, 1, 2, 200, rules.clone(), 0).players[0].network.clone();
+        let inputs = (0..Bot::inputs(2, &rules)).map(|i| (i as f32).sin()).collect::<Vec<_>>();
+
+        let path = std::env::temp_dir().join("fish_core_network_test.json");
+        network.save(&path).unwrap();
+        let mut loaded = NeuralNetwork::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.eval(&inputs).unwrap(), network.eval(&inputs).unwrap());
+    }
+
+    #[test]
+    fn networks_without_layers_are_rejected() {
+        let mut empty: NeuralNetwork = serde_json::from_str(r#"{"layers":[]}"#).unwrap();
+        assert!(matches!(empty.eval(&[]), Err(GoFishError::EmptyNetwork)));
+
+        let path = std::env::temp_dir().join("fish_core_empty_network_test.json");
+        empty.save(&path).unwrap();
+        let loaded = NeuralNetwork::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(loaded, Err(GoFishError::EmptyNetwork)));
+    }
+}
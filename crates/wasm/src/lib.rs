@@ -1,56 +1,52 @@
-use fish_core::{observer::{GameObserver, DECK_SIZE, KnownDeck}, game::{Bot, INPUTS_PER_UNKNOWN_CARD}, neural_network::NeuralNetwork, trainer::BotTrainer, util};
+use std::cell::RefCell;
+
+use fish_core::{observer::GameObserver, game::Bot, neural_network::{Activation, NeuralNetwork}, rules::RuleSet, util};
 use rand::Rng;
 use wasm_bindgen::prelude::*;
-use rand::thread_rng;
 
 extern "C" {
 
 }
 
-static mut BOT: Option<Bot> = None;
+thread_local! {
+    static BOT: RefCell<Option<Bot>> = const { RefCell::new(None) };
+}
 
 #[wasm_bindgen]
-pub fn rand() -> String {
-    thread_rng().gen_range(0..10).to_string()
+pub fn rand(seed: u64) -> String {
+    util::rng_from_seed(seed).gen_range(0..10).to_string()
 }
 
 #[wasm_bindgen]
-pub fn init(game_size: usize, deck: Option<Vec<usize>>, position_in_game: usize) {
+pub fn init(game_size: usize, deck: Option<Vec<usize>>, position_in_game: usize, seed: u64) {
     console_error_panic_hook::set_once();
 
-    let mut new_deck = [0; DECK_SIZE];
-
-    if let Some(deck) = &deck {
-        new_deck.copy_from_slice(deck);
-    }
-    
-    let mut rng = thread_rng();
+    let rules = RuleSet::default();
+    let mut rng = util::rng_from_seed(seed);
 
-    unsafe {
-        BOT = Some(Bot::new(GameObserver::new(game_size - 1, deck.map(|_| new_deck), position_in_game),
-            &NeuralNetwork::new_rand(
-            game_size * DECK_SIZE * INPUTS_PER_UNKNOWN_CARD + DECK_SIZE + 1,
-            &[
-                ((game_size - 1) * DECK_SIZE, util::ac_tanh),
-                ((game_size - 1) * DECK_SIZE, util::ac_softmax),
-            ],
-            &mut rng
-        )));
-    }
+    BOT.set(Some(Bot::new(GameObserver::new(game_size - 1, deck, position_in_game, &rules),
+        &NeuralNetwork::new_rand(
+        Bot::inputs(game_size, &rules),
+        &[
+            (Bot::outputs(game_size, &rules), Activation::Tanh),
+            (Bot::outputs(game_size, &rules), Activation::Softmax),
+        ],
+        &mut rng
+    ))));
 
 
-    // let mut rng = thread_rng();
     // let mut trainer = BotTrainer::new(
     //     100,
     //     0.1,
     //     40,
     //     4,
-    //     50,
-    //     &mut rng
+    //     1000,
+    //     RuleSet::default(),
+    //     seed
     // );
 
     // for i in 0..100 {
-    //     trainer.step(&mut rng).unwrap();
+    //     trainer.step(util::derive_seed(seed, i)).unwrap();
     //     web_sys::console::log_1(&JsValue::from_str(format!("Game {i}: Top Fitness: {}", trainer.players.iter().max_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap()).unwrap().fitness).as_str()));
     // }
 
@@ -59,8 +55,8 @@ pub fn init(game_size: usize, deck: Option<Vec<usize>>, position_in_game: usize)
 
 #[wasm_bindgen]
 pub fn get_observer() -> JsValue {
-    match unsafe { BOT.as_ref() } {
+    BOT.with_borrow(|bot| match bot {
         Some(bot) => serde_wasm_bindgen::to_value(&bot.observer).unwrap(),
         None => JsValue::NULL,
-    }
+    })
 }
\ No newline at end of file
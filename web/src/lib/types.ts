@@ -2,7 +2,20 @@ export type GameObserver = {
     deck: Deck,
     otherPlayers: Player[],
     ownDeck: KnownDeck | null,
+    placed: KnownDeck,
     id: number,
+    rules: RuleSet,
+}
+
+export type RuleSet = {
+    ranks: number,
+    suits: number,
+    smallGameHand: number,
+    largeGameHand: number,
+    largeGamePlayers: number,
+    bookSize: number,
+    continueOnSuccess: boolean,
+    emptyHand: "Draw" | "Refill" | "SitOut",
 }
 
 export type Deck = {
@@ -19,4 +32,9 @@ export type Player = {
     cards: Probability[],
 }
 
-export type KnownDeck = number[]
\ No newline at end of file
+export type KnownDeck = number[]
+
+export type Card = {
+    rank: number,
+    suit: number,
+}
\ No newline at end of file